@@ -1,9 +1,12 @@
-use std::{env, fs::File, path::Path};
+use std::{collections::BTreeSet, env, fs::File, path::Path};
 
 use criterion::{Criterion, criterion_group, criterion_main};
 use lev_automaton::{
-    automaton::Deterministic, levenshtein_automaton::LevenshteinAutomaton,
-    spell_checker::SpellChecker, trie::Trie,
+    automaton::{Automaton, Deterministic, NonDeterministic, StateId},
+    levenshtein_automaton::LevenshteinAutomaton,
+    matcher::Matcher,
+    spell_checker::SpellChecker,
+    trie::Trie,
 };
 
 fn lev_automaton_bench(c: &mut Criterion) {
@@ -29,13 +32,91 @@ fn lev_automaton_bench(c: &mut Criterion) {
                 }
             });
         });
+
+        let misspelled: Vec<&str> = words
+            .iter()
+            .map(|(misspelled, _)| misspelled.as_str())
+            .collect();
+        group.bench_function(format!("degree: {degree} (parallel)"), |b| {
+            b.iter(|| spell_checker.suggest_many(&misspelled));
+        });
+    }
+}
+
+struct Unpruned<'a, M>(&'a M);
+
+impl Matcher for Unpruned<'_, Automaton<NonDeterministic>> {
+    type State = BTreeSet<StateId>;
+
+    fn start(&self) -> Self::State {
+        self.0.start()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        self.0.accept(state, c)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.0.is_match(state)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        !state.is_empty()
+    }
+}
+
+impl Matcher for Unpruned<'_, Automaton<Deterministic>> {
+    type State = Option<StateId>;
+
+    fn start(&self) -> Self::State {
+        self.0.start_state()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        self.0.step((*state)?, c)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.0.is_match(state)
+    }
+}
+
+fn intersection_bench(c: &mut Criterion) {
+    let path = env::var("LEV_SPELL_CHECK_DICT_PATH").unwrap_or("/usr/share/dict/words".into());
+    let trie = Trie::load_from_file(Path::new(&path))
+        .unwrap_or_else(|_| panic!("Unable to open dictionary file: {path}"));
+    let mut group = c.benchmark_group("Trie intersection");
+
+    for min_part_len in [3, 8] {
+        let compounds: Automaton<NonDeterministic> =
+            trie.get_automaton().kleene_closure(&["s"], min_part_len);
+
+        group.bench_function(format!("compounds: {min_part_len}, pruning: false"), |b| {
+            b.iter(|| trie.filter(&Unpruned(&compounds)))
+        });
+        group.bench_function(format!("compounds: {min_part_len}, pruning: true"), |b| {
+            b.iter(|| trie.filter(&compounds))
+        });
+    }
+
+    for degree in 1..=2 {
+        let aut: LevenshteinAutomaton<Deterministic> =
+            LevenshteinAutomaton::new("intersection", degree).into();
+        let dfa = aut.get_automaton();
+
+        group.bench_function(format!("levenshtein: {degree}, pruning: false"), |b| {
+            b.iter(|| trie.filter(&Unpruned(dfa)))
+        });
+        group.bench_function(format!("levenshtein: {degree}, pruning: true"), |b| {
+            b.iter(|| trie.filter(dfa))
+        });
     }
 }
 
 criterion_group! {
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = lev_automaton_bench
+    targets = lev_automaton_bench, intersection_bench
 }
 
 criterion_main!(benches);
@@ -1,8 +1,11 @@
-use std::{env, fs::File, path::Path};
+use std::{env, fs, fs::File, path::Path};
 
 use lev_automaton::{
-    automaton::Deterministic, levenshtein_automaton::LevenshteinAutomaton,
-    spell_checker::SpellChecker, trie::Trie,
+    automaton::Deterministic,
+    error_model::{ChannelCorrector, ErrorModel, NoisyChannel, parse_frequencies},
+    levenshtein_automaton::LevenshteinAutomaton,
+    spell_checker::{Corrector, EscalationPolicy, SpellChecker},
+    trie::Trie,
 };
 
 fn main() {
@@ -24,44 +27,106 @@ fn main() {
             trie.filter(aut.get_automaton())
         });
 
-        let mut unambiguous_corrections = 0;
-        let mut ambiguous_corrections = 0;
-        let mut not_corrected = 0;
-        for (misspelled, correct) in words.iter() {
-            let res = spell_checker.check_word(misspelled);
-            match res {
-                Ok(()) => {}
-                Err(corrections) => {
-                    if corrections.iter().any(|c| c == correct) {
-                        if corrections.len() == 1 {
-                            unambiguous_corrections += 1;
-                        } else {
-                            ambiguous_corrections += 1;
-                        }
+        report(
+            &format!("Levenshtein automaton of degree: {degree}"),
+            &spell_checker,
+            &words,
+        );
+    }
+
+    let policy = EscalationPolicy::default();
+    let spell_checker = SpellChecker::new(trie.clone(), |word, trie| policy.correct(word, trie));
+    report("Adaptive escalation", &spell_checker, &words);
+
+    let (held_out, training): (Vec<_>, Vec<_>) = words
+        .iter()
+        .cloned()
+        .enumerate()
+        .partition(|(i, _)| i % 10 == 0);
+    let held_out: Vec<_> = held_out.into_iter().map(|(_, pair)| pair).collect();
+    let error_model = ErrorModel::train(
+        training
+            .iter()
+            .map(|(_, (misspelled, correct))| (misspelled.as_str(), correct.as_str())),
+    );
+    let mut channel = NoisyChannel::new(error_model);
+    match env::var("LEV_SPELL_CHECK_FREQ_PATH") {
+        Ok(path) => {
+            let frequencies = fs::read_to_string(&path)
+                .unwrap_or_else(|_| panic!("Unable to open frequency list: {path}"));
+            channel.add_word_frequencies(parse_frequencies(&frequencies));
+        }
+        Err(_) => {
+            channel.add_word_counts(training.iter().map(|(_, (_, correct))| correct.as_str()))
+        }
+    }
+
+    report("Adaptive escalation (held-out)", &spell_checker, &held_out);
+
+    let spell_checker =
+        SpellChecker::from_corrector(trie.clone(), ChannelCorrector::new(policy, channel));
+    report(
+        "Noisy channel ranking (held-out)",
+        &spell_checker,
+        &held_out,
+    );
+}
+
+fn report<F>(name: &str, spell_checker: &SpellChecker<F>, words: &[(String, String)])
+where
+    F: Corrector + Sync,
+{
+    let misspelled: Vec<&str> = words
+        .iter()
+        .map(|(misspelled, _)| misspelled.as_str())
+        .collect();
+    let results = spell_checker.suggest_many(&misspelled);
+
+    let mut unambiguous_corrections = 0;
+    let mut ambiguous_corrections = 0;
+    let mut not_corrected = 0;
+    let mut top_ranked = 0;
+    for ((_, correct), res) in words.iter().zip(results) {
+        match res {
+            Ok(()) => {}
+            Err(corrections) => {
+                if corrections.first() == Some(correct) {
+                    top_ranked += 1;
+                }
+                if corrections.iter().any(|c| c == correct) {
+                    if corrections.len() == 1 {
+                        unambiguous_corrections += 1;
                     } else {
-                        not_corrected += 1;
+                        ambiguous_corrections += 1;
                     }
+                } else {
+                    not_corrected += 1;
                 }
             }
         }
-
-        println!("Levenshtein automaton of degree: {degree}");
-        println!("Word count:               {}", words.len());
-        println!(
-            "Unambiguous corrections:  {} ({:.2}%)",
-            unambiguous_corrections,
-            unambiguous_corrections as f64 / words.len() as f64 * 100.0
-        );
-        println!(
-            "Ambiguous corrections:    {} ({:.2}%)",
-            ambiguous_corrections,
-            ambiguous_corrections as f64 / words.len() as f64 * 100.0
-        );
-        println!(
-            "Not corrected:            {} ({:.2}%)",
-            not_corrected,
-            not_corrected as f64 / words.len() as f64 * 100.0
-        );
-        println!();
     }
+
+    println!("{name}");
+    println!("Word count:               {}", words.len());
+    println!(
+        "Unambiguous corrections:  {} ({:.2}%)",
+        unambiguous_corrections,
+        unambiguous_corrections as f64 / words.len() as f64 * 100.0
+    );
+    println!(
+        "Ambiguous corrections:    {} ({:.2}%)",
+        ambiguous_corrections,
+        ambiguous_corrections as f64 / words.len() as f64 * 100.0
+    );
+    println!(
+        "Top ranked correction:    {} ({:.2}%)",
+        top_ranked,
+        top_ranked as f64 / words.len() as f64 * 100.0
+    );
+    println!(
+        "Not corrected:            {} ({:.2}%)",
+        not_corrected,
+        not_corrected as f64 / words.len() as f64 * 100.0
+    );
+    println!();
 }
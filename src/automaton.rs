@@ -1,9 +1,14 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write as _,
+    io::{self, ErrorKind, Read, Write},
     marker::PhantomData,
     ops::{Index, IndexMut},
+    sync::OnceLock,
 };
 
+use crate::matcher::Matcher;
+
 type Set<T> = BTreeSet<T>;
 type Map<K, V> = BTreeMap<K, V>;
 
@@ -85,6 +90,7 @@ pub struct Automaton<T> {
     states: Vec<State>,
     start: Option<StateId>,
     final_states: Set<StateId>,
+    live: OnceLock<Vec<bool>>,
     _determinism_marker: PhantomData<T>,
 }
 
@@ -94,6 +100,7 @@ impl<T> Default for Automaton<T> {
             states: vec![],
             start: None,
             final_states: Set::new(),
+            live: OnceLock::new(),
             _determinism_marker: PhantomData,
         }
     }
@@ -115,6 +122,7 @@ impl<T> IndexMut<StateId> for Automaton<T> {
 
 impl<T> Automaton<T> {
     pub fn add_state(&mut self) -> StateId {
+        self.live.take();
         self.states.push(State::new());
         StateId(self.states.len() - 1)
     }
@@ -124,9 +132,173 @@ impl<T> Automaton<T> {
     }
 
     pub fn make_state_final(&mut self, index: StateId) {
+        self.live.take();
         self.final_states.insert(index);
     }
 
+    pub fn is_live(&self, state: StateId) -> bool {
+        self.live_states()[state.0]
+    }
+
+    pub fn live_states(&self) -> &[bool] {
+        self.live.get_or_init(|| {
+            let mut incoming = vec![vec![]; self.states.len()];
+            for (from, state) in self.states.iter().enumerate() {
+                for (_, to) in &state.transitions {
+                    incoming[to.0].push(from);
+                }
+            }
+
+            let mut live = vec![false; self.states.len()];
+            let mut stack: Vec<usize> = self.final_states.iter().map(|s| s.0).collect();
+            for state in &stack {
+                live[*state] = true;
+            }
+            while let Some(state) = stack.pop() {
+                for from in &incoming[state] {
+                    if !live[*from] {
+                        live[*from] = true;
+                        stack.push(*from);
+                    }
+                }
+            }
+            live
+        })
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n    rankdir=LR;\n    node [shape=circle];\n");
+        for state in &self.final_states {
+            _ = writeln!(dot, "    {} [shape=doublecircle];", state.0);
+        }
+        if let Some(start) = self
+            .start
+            .or((!self.states.is_empty()).then_some(StateId(0)))
+        {
+            _ = writeln!(dot, "    start [shape=point];\n    start -> {};", start.0);
+        }
+        for (from, state) in self.states.iter().enumerate() {
+            for (transition, to) in &state.transitions {
+                let label = match transition {
+                    Transition::Is(c) => c.escape_default().to_string(),
+                    Transition::IsNot(cs) => format!(
+                        "not {{{}}}",
+                        cs.iter()
+                            .map(|c| c.escape_default().to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    ),
+                    Transition::Star => "*".to_string(),
+                    Transition::Epsilon => "ε".to_string(),
+                };
+                _ = writeln!(
+                    dot,
+                    "    {from} -> {} [label=\"{}\"];",
+                    to.0,
+                    label.replace('"', "\\\"")
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let write_u32 = |writer: &mut W, n: usize| writer.write_all(&(n as u32).to_le_bytes());
+
+        write_u32(writer, self.states.len())?;
+        write_u32(writer, self.start.map_or(0, |s| s.0))?;
+        write_u32(writer, self.final_states.len())?;
+        for state in &self.final_states {
+            write_u32(writer, state.0)?;
+        }
+
+        for state in &self.states {
+            write_u32(writer, state.transitions.len())?;
+            for (transition, to) in &state.transitions {
+                match transition {
+                    Transition::Is(c) => {
+                        writer.write_all(&[0])?;
+                        write_u32(writer, *c as usize)?;
+                    }
+                    Transition::IsNot(cs) => {
+                        writer.write_all(&[1])?;
+                        write_u32(writer, cs.len())?;
+                        for c in cs {
+                            write_u32(writer, *c as usize)?;
+                        }
+                    }
+                    Transition::Star => writer.write_all(&[2])?,
+                    Transition::Epsilon => writer.write_all(&[3])?,
+                }
+                write_u32(writer, to.0)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_states<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+        let read_u32 = |reader: &mut R| -> io::Result<usize> {
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf)?;
+            Ok(u32::from_le_bytes(buf) as usize)
+        };
+        let read_char = |reader: &mut R| -> io::Result<char> {
+            char::from_u32(read_u32(reader)? as u32).ok_or_else(|| invalid("invalid char"))
+        };
+
+        let state_count = read_u32(reader)?;
+        let read_state = |reader: &mut R| -> io::Result<StateId> {
+            let id = read_u32(reader)?;
+            (id < state_count)
+                .then_some(StateId(id))
+                .ok_or_else(|| invalid("state id out of range"))
+        };
+
+        let mut automaton = Self::default();
+        let start = read_state(reader);
+        let final_count = read_u32(reader)?;
+        for _ in 0..final_count {
+            let state = read_state(reader)?;
+            automaton.make_state_final(state);
+        }
+
+        for _ in 0..state_count {
+            let mut state = State::new();
+            for _ in 0..read_u32(reader)? {
+                let mut tag = [0];
+                reader.read_exact(&mut tag)?;
+                let transition = match tag[0] {
+                    0 => Transition::Is(read_char(reader)?),
+                    1 => {
+                        let count = read_u32(reader)?;
+                        Transition::IsNot(
+                            (0..count)
+                                .map(|_| read_char(reader))
+                                .collect::<io::Result<_>>()?,
+                        )
+                    }
+                    2 => Transition::Star,
+                    3 => Transition::Epsilon,
+                    _ => return Err(invalid("invalid transition tag")),
+                };
+                state.transitions.push((transition, read_state(reader)?));
+            }
+            automaton.states.push(state);
+        }
+        if state_count > 0 {
+            automaton.set_start_state(start?);
+        }
+
+        Ok(automaton)
+    }
+
+    pub fn transitions(&self, state: StateId) -> &[(Transition, StateId)] {
+        &self[state].transitions
+    }
+
     fn get_reachable_states<P>(&self, from: StateId, pred: P) -> impl Iterator<Item = StateId>
     where
         P: Fn(&Transition) -> bool,
@@ -140,7 +312,12 @@ impl<T> Automaton<T> {
 }
 
 impl Automaton<NonDeterministic> {
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Self::read_states(reader)
+    }
+
     pub fn add_transition(&mut self, from: StateId, to: StateId, transition: Transition) {
+        self.live.take();
         self[from].transitions.push((transition, to));
     }
 
@@ -169,6 +346,56 @@ impl Automaton<NonDeterministic> {
         active_states.iter().any(|s| self.final_states.contains(s))
     }
 
+    pub fn intersect(&self, other: &Automaton<Deterministic>) -> Vec<String> {
+        let (Some(other_start), false) = (other.start_state(), self.states.is_empty()) else {
+            return vec![];
+        };
+        let self_start = self.start();
+        if !self.can_match(&self_start) || !other.is_live(other_start) {
+            return vec![];
+        }
+
+        let mut words = vec![];
+        let mut stack = vec![(String::new(), self_start, other_start)];
+        while let Some((word, self_states, other_state)) = stack.pop() {
+            let chars: Set<char> = self_states
+                .iter()
+                .flat_map(|s| self[*s].transitions.iter())
+                .chain(other[other_state].transitions.iter())
+                .filter_map(|(transition, _)| match transition {
+                    Transition::Is(c) => Some(*c),
+                    _ => None,
+                })
+                .collect();
+
+            for c in chars {
+                let Some(new_other_state) =
+                    other.step(other_state, c).filter(|s| other.is_live(*s))
+                else {
+                    continue;
+                };
+                let mut new_self_states = self.accept(&self_states, c);
+                new_self_states.retain(|s| self.is_live(*s));
+                if new_self_states.is_empty() {
+                    continue;
+                }
+
+                let mut new_word = word.clone();
+                new_word.push(c);
+                if other.is_final(new_other_state)
+                    && new_self_states
+                        .iter()
+                        .any(|s| self.final_states.contains(s))
+                {
+                    words.push(new_word.clone());
+                }
+                stack.push((new_word, new_self_states, new_other_state));
+            }
+        }
+
+        words
+    }
+
     fn eps_closure(&self, mut states: Set<StateId>) -> Set<StateId> {
         let mut new_states: Vec<StateId> = states.iter().copied().collect();
 
@@ -296,6 +523,25 @@ impl Automaton<Deterministic> {
         words
     }
 
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let automaton = Self::read_states(reader)?;
+        for state in &automaton.states {
+            for (i, (transition, _)) in state.transitions.iter().enumerate() {
+                if transition.is_epsilon()
+                    || state.transitions[..i]
+                        .iter()
+                        .any(|(t, _)| t.have_overlap(transition))
+                {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidData,
+                        "automaton is not deterministic",
+                    ));
+                }
+            }
+        }
+        Ok(automaton)
+    }
+
     #[must_use]
     pub fn add_transition(&mut self, from: StateId, to: StateId, transition: Transition) -> bool {
         if self[from]
@@ -306,32 +552,146 @@ impl Automaton<Deterministic> {
             return false;
         }
 
+        self.live.take();
         self[from].transitions.push((transition, to));
         true
     }
 
-    pub fn recognizes(&self, word: &str) -> bool {
-        if self.states.is_empty() {
-            return false;
+    pub fn kleene_closure(&self, linkers: &[&str], min_len: usize) -> Automaton<NonDeterministic> {
+        let mut nfa = Automaton::<NonDeterministic>::default();
+        let Some(start) = self.start_state() else {
+            return nfa;
+        };
+
+        for state in &self.states {
+            let new_state = nfa.add_state();
+            nfa[new_state].transitions = state.transitions.clone();
+        }
+        nfa.set_start_state(start);
+
+        let mut depths = vec![None; self.states.len()];
+        depths[start.0] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(state) = queue.pop_front() {
+            let depth = depths[state.0].unwrap_or(0);
+            for (_, to) in &self[state].transitions {
+                if depths[to.0].is_none() {
+                    depths[to.0] = Some(depth + 1);
+                    queue.push_back(*to);
+                }
+            }
         }
 
-        let mut active_state = self.start.unwrap_or(StateId(0));
+        let part_ends: Vec<StateId> = self
+            .final_states
+            .iter()
+            .filter(|s| depths[s.0].is_some_and(|depth| depth >= min_len))
+            .copied()
+            .collect();
 
-        for c in word.chars() {
-            let transitions: Vec<_> = self[active_state]
-                .transitions
-                .iter()
-                .filter(|(transition, _)| transition.allows(c))
-                .map(|(_, to)| to)
-                .collect();
+        for part_end in &part_ends {
+            nfa.make_state_final(*part_end);
+            nfa.add_transition(*part_end, start, Transition::Epsilon);
+        }
+
+        for linker in linkers.iter().filter(|l| !l.is_empty()) {
+            let linker_start = nfa.add_state();
+            let mut current = linker_start;
+            let mut chars = linker.chars().peekable();
+            while let Some(c) = chars.next() {
+                let next = if chars.peek().is_some() {
+                    nfa.add_state()
+                } else {
+                    start
+                };
+                nfa.add_transition(current, next, Transition::Is(c));
+                current = next;
+            }
+
+            for part_end in &part_ends {
+                nfa.add_transition(*part_end, linker_start, Transition::Epsilon);
+            }
+        }
 
-            if let Some(new_state) = transitions.into_iter().next() {
-                active_state = *new_state
+        nfa
+    }
+
+    pub fn start_state(&self) -> Option<StateId> {
+        (!self.states.is_empty()).then(|| self.start.unwrap_or(StateId(0)))
+    }
+
+    pub fn step(&self, state: StateId, c: char) -> Option<StateId> {
+        self[state]
+            .transitions
+            .iter()
+            .find(|(transition, _)| transition.allows(c))
+            .map(|(_, to)| *to)
+    }
+
+    pub fn is_final(&self, state: StateId) -> bool {
+        self.final_states.contains(&state)
+    }
+
+    pub fn recognizes(&self, word: &str) -> bool {
+        let Some(mut active_state) = self.start_state() else {
+            return false;
+        };
+
+        for c in word.chars() {
+            if let Some(new_state) = self.step(active_state, c) {
+                active_state = new_state
             } else {
                 return false;
             }
         }
 
-        self.final_states.contains(&active_state)
+        self.is_final(active_state)
+    }
+}
+
+impl Matcher for Automaton<NonDeterministic> {
+    type State = Set<StateId>;
+
+    fn start(&self) -> Self::State {
+        match self.states.is_empty() {
+            true => Set::new(),
+            false => self.eps_closure(Set::from([self.start.unwrap_or(StateId(0))])),
+        }
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        let states = state
+            .iter()
+            .flat_map(|s| self.get_reachable_states(*s, |t| t.allows(c)))
+            .collect();
+        self.eps_closure(states)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state.iter().any(|s| self.final_states.contains(s))
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.iter().any(|s| self.is_live(*s))
+    }
+}
+
+impl Matcher for Automaton<Deterministic> {
+    type State = Option<StateId>;
+
+    fn start(&self) -> Self::State {
+        self.start_state()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        self.step((*state)?, c).filter(|s| self.is_live(*s))
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state.is_some_and(|s| self.is_final(s))
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
     }
 }
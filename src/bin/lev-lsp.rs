@@ -0,0 +1,32 @@
+use std::{env, io, path::PathBuf, sync::Arc, time::Duration};
+
+use lev_automaton::{
+    dictionary::DictionaryStack,
+    lsp::Server,
+    reload::Reloadable,
+    segmentation::Segmenter,
+    spell_checker::{EscalationPolicy, SpellChecker},
+};
+
+fn main() {
+    let paths = env::var("LEV_SPELL_CHECK_DICT_PATH").unwrap_or("/usr/share/dict/words".into());
+    let paths: Vec<PathBuf> = env::split_paths(&paths).collect();
+    let personal = env::var_os("LEV_SPELL_CHECK_PERSONAL_DICT").map(PathBuf::from);
+    let mut watched = paths.clone();
+    watched.extend(personal.clone());
+
+    let handle = Reloadable::new(move || {
+        let dictionaries = DictionaryStack::load(&paths, personal.as_deref())?;
+        Ok(
+            SpellChecker::from_corrector(dictionaries, EscalationPolicy::default())
+                .with_segmenter(Segmenter::default()),
+        )
+    })
+    .unwrap_or_else(|e| panic!("Unable to open dictionary files {watched:?}: {e}"));
+    let handle = Arc::new(handle.with_watched(&watched));
+    handle.watch(Duration::from_secs(2));
+
+    Server::new(handle)
+        .run(io::stdin().lock(), io::stdout().lock())
+        .expect("STDIO FAIL");
+}
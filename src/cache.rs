@@ -0,0 +1,125 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            stats: CacheStats {
+                capacity,
+                ..CacheStats::default()
+            },
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let Some((value, last_used)) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.stats.hits += 1;
+        self.tick += 1;
+        let key = self
+            .recency
+            .remove(last_used)
+            .expect("recency index out of sync");
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(value.clone())
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.stats.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.recency.remove(&last_used);
+        } else if self.entries.len() > self.stats.capacity
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.entries.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+        self.recency.insert(self.tick, key);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            len: self.entries.len(),
+            ..self.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cache::LruCache;
+
+    #[test]
+    fn test_lru_cache() {
+        let mut cache = LruCache::new(2);
+        cache.insert("teh", 1);
+        cache.insert("recieve", 2);
+        assert_eq!(cache.get(&"teh"), Some(1));
+
+        cache.insert("wrld", 3);
+        assert_eq!(cache.get(&"recieve"), None);
+        assert_eq!(cache.get(&"teh"), Some(1));
+        assert_eq!(cache.get(&"wrld"), Some(3));
+
+        cache.insert("wrld", 4);
+        assert_eq!(cache.get(&"wrld"), Some(4));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (4, 1, 1));
+        assert_eq!((stats.len, stats.capacity), (2, 2));
+        assert_eq!(stats.hit_rate(), 0.8);
+
+        cache.clear();
+        assert_eq!(cache.get(&"teh"), None);
+
+        let mut disabled = LruCache::new(0);
+        disabled.insert("teh", 1);
+        assert_eq!(disabled.get(&"teh"), None);
+    }
+}
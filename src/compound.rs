@@ -0,0 +1,165 @@
+use crate::{
+    automaton::{Automaton, Deterministic, NonDeterministic},
+    levenshtein_automaton::LevenshteinAutomaton,
+    trie::Trie,
+};
+
+#[derive(Debug, Clone)]
+pub struct CompoundConfig {
+    pub linkers: Vec<String>,
+    pub min_part_len: usize,
+    pub fuzzy_degree: usize,
+}
+
+impl Default for CompoundConfig {
+    fn default() -> Self {
+        Self {
+            linkers: vec![],
+            min_part_len: 3,
+            fuzzy_degree: 1,
+        }
+    }
+}
+
+impl CompoundConfig {
+    pub fn german() -> Self {
+        Self {
+            linkers: ["s", "es", "n", "en", "er", "e"].map(String::from).to_vec(),
+            ..Self::default()
+        }
+    }
+
+    pub fn dutch() -> Self {
+        Self {
+            linkers: ["s", "e", "en"].map(String::from).to_vec(),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Compounds {
+    automaton: Automaton<NonDeterministic>,
+    fuzzy_degree: usize,
+}
+
+impl Compounds {
+    pub fn new(trie: &Trie, config: &CompoundConfig) -> Self {
+        let linkers: Vec<&str> = config.linkers.iter().map(String::as_str).collect();
+        Self {
+            automaton: trie
+                .get_automaton()
+                .kleene_closure(&linkers, config.min_part_len),
+            fuzzy_degree: config.fuzzy_degree,
+        }
+    }
+
+    pub fn recognizes(&self, word: &str) -> bool {
+        self.automaton.recognizes(word)
+    }
+
+    pub fn fuzzy(&self, word: &str) -> Vec<String> {
+        let aut: LevenshteinAutomaton<Deterministic> =
+            LevenshteinAutomaton::new(word, self.fuzzy_degree).into();
+        self.automaton.intersect(aut.get_automaton())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        automaton::{Automaton, NonDeterministic, StateId},
+        compound::{CompoundConfig, Compounds},
+        matcher::Matcher,
+        trie::Trie,
+    };
+
+    const WORDS: [&str; 7] = ["haus", "tür", "schlüssel", "bund", "arbeit", "amt", "zu"];
+
+    #[test]
+    fn test_compounds() {
+        let trie = Trie::new(&mut WORDS.to_vec());
+        let compounds = Compounds::new(&trie, &CompoundConfig::german());
+
+        assert!(compounds.recognizes("haustür"));
+        assert!(compounds.recognizes("schlüsselbund"));
+        assert!(compounds.recognizes("haustürschlüssel"));
+        assert!(compounds.recognizes("arbeitsamt"));
+        assert!(compounds.recognizes("haus"));
+        assert!(!compounds.recognizes("hausx"));
+        assert!(!compounds.recognizes("hausxamt"));
+        assert!(!compounds.recognizes("zuhaus"));
+        assert!(!compounds.recognizes("haussss"));
+
+        let no_linkers = Compounds::new(&trie, &CompoundConfig::default());
+        assert!(!no_linkers.recognizes("arbeitsamt"));
+
+        let short_parts = Compounds::new(
+            &trie,
+            &CompoundConfig {
+                min_part_len: 2,
+                ..CompoundConfig::default()
+            },
+        );
+        assert!(short_parts.recognizes("zuhaus"));
+    }
+
+    #[test]
+    fn test_dead_states() {
+        let trie = Trie::new(&mut WORDS.to_vec());
+        let compounds = Compounds::new(&trie, &CompoundConfig::default());
+        let automaton = &compounds.automaton;
+
+        assert!(!automaton.can_match(&automaton.accept(&automaton.start(), 'z')));
+        assert!(automaton.can_match(&automaton.accept(&automaton.start(), 'h')));
+        assert!(trie.filter(automaton).iter().all(|w| w.len() >= 3));
+
+        struct Unpruned<'a>(&'a Automaton<NonDeterministic>);
+        impl Matcher for Unpruned<'_> {
+            type State = BTreeSet<StateId>;
+
+            fn start(&self) -> Self::State {
+                self.0.start()
+            }
+
+            fn accept(&self, state: &Self::State, c: char) -> Self::State {
+                self.0.accept(state, c)
+            }
+
+            fn is_match(&self, state: &Self::State) -> bool {
+                self.0.is_match(state)
+            }
+
+            fn can_match(&self, state: &Self::State) -> bool {
+                !state.is_empty()
+            }
+        }
+        let mut pruned = trie.filter(automaton);
+        let mut unpruned = trie.filter(&Unpruned(automaton));
+        pruned.sort();
+        unpruned.sort();
+        assert!(!pruned.is_empty());
+        assert_eq!(pruned, unpruned);
+    }
+
+    #[test]
+    fn test_fuzzy_compounds() {
+        let trie = Trie::new(&mut WORDS.to_vec());
+        let compounds = Compounds::new(&trie, &CompoundConfig::german());
+
+        assert_eq!(compounds.fuzzy("haustüt"), vec!["haustür"]);
+
+        let mut corrections = compounds.fuzzy("arbeitsant");
+        corrections.sort();
+        assert_eq!(corrections, vec!["arbeitsamt"]);
+
+        let mut corrections = compounds.fuzzy("arbeitamt");
+        corrections.sort();
+        assert_eq!(
+            corrections,
+            vec!["arbeitamt", "arbeiteamt", "arbeitnamt", "arbeitsamt"]
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use std::{
+    io::{self, BufRead, BufReader, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    thread,
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    automaton::Deterministic,
+    levenshtein_automaton::LevenshteinAutomaton,
+    reload::Reloadable,
+    spell_checker::{Corrector, SpellChecker, rank_suggestions},
+};
+
+pub const METHODS: [&str; 3] = ["check", "suggest", "fuzzy-query"];
+
+pub const MAX_DISTANCE: usize = 3;
+
+const MAX_BODY_LEN: usize = 1 << 20;
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub fn handle_request<F>(spell_checker: &SpellChecker<F>, request: &Value) -> Value
+where
+    F: Corrector,
+{
+    match request["method"].as_str().unwrap_or_default() {
+        "check" => {
+            let Some(text) = request["text"].as_str() else {
+                return json!({ "error": "missing text" });
+            };
+            let misspellings: Vec<Value> = spell_checker
+                .check_text(text)
+                .into_iter()
+                .map(|m| {
+                    json!({
+                        "line": m.line,
+                        "column": m.column,
+                        "start": m.byte_range.start,
+                        "end": m.byte_range.end,
+                        "word": m.word,
+                        "suggestions": m.suggestions,
+                    })
+                })
+                .collect();
+            json!({ "misspellings": misspellings })
+        }
+        "suggest" => {
+            let Some(word) = request["word"].as_str() else {
+                return json!({ "error": "missing word" });
+            };
+            match spell_checker.check_word(word) {
+                Ok(()) => json!({ "correct": true, "suggestions": [] }),
+                Err(suggestions) => json!({ "correct": false, "suggestions": suggestions }),
+            }
+        }
+        "fuzzy-query" => {
+            let Some(word) = request["word"].as_str() else {
+                return json!({ "error": "missing word" });
+            };
+            let distance = request["distance"].as_u64().unwrap_or(1);
+            if distance > MAX_DISTANCE as u64 {
+                return json!({ "error": format!("distance must be at most {MAX_DISTANCE}") });
+            }
+            let distance = distance as usize;
+            let aut: LevenshteinAutomaton<Deterministic> =
+                LevenshteinAutomaton::new(word, distance).into();
+            let mut words = spell_checker
+                .dictionaries()
+                .main()
+                .filter(aut.get_automaton());
+            rank_suggestions(word, &mut words);
+            json!({ "words": words })
+        }
+        method => json!({ "error": format!("unknown method: {method}") }),
+    }
+}
+
+fn handle_lines<F, S>(handle: &Reloadable<SpellChecker<F>>, stream: S) -> io::Result<()>
+where
+    F: Corrector,
+    S: Read + Write,
+{
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while reader
+        .by_ref()
+        .take(MAX_BODY_LEN as u64 + 1)
+        .read_line(&mut line)?
+        > 0
+    {
+        if line.len() > MAX_BODY_LEN {
+            writeln!(
+                reader.get_mut(),
+                "{}",
+                json!({ "error": "request too large" })
+            )?;
+            break;
+        }
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => handle_request(&handle.snapshot(), &request),
+            Err(e) => json!({ "error": format!("invalid request: {e}") }),
+        };
+        writeln!(reader.get_mut(), "{response}")?;
+        line.clear();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn serve_unix<F>(handle: &Reloadable<SpellChecker<F>>, listener: UnixListener) -> io::Result<()>
+where
+    F: Corrector + Send + Sync,
+{
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("accept failed: {e}");
+                    continue;
+                }
+            };
+            scope.spawn(move || {
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                handle_lines(handle, stream)
+            });
+        }
+        Ok(())
+    })
+}
+
+#[cfg(unix)]
+pub fn request_unix(path: &Path, request: &Value) -> io::Result<Value> {
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{request}")?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    serde_json::from_str(&response).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+pub fn serve_http<F>(handle: &Reloadable<SpellChecker<F>>, listener: TcpListener) -> io::Result<()>
+where
+    F: Corrector + Send + Sync,
+{
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("accept failed: {e}");
+                    continue;
+                }
+            };
+            scope.spawn(move || {
+                stream.set_read_timeout(Some(READ_TIMEOUT))?;
+                handle_http(handle, stream)
+            });
+        }
+        Ok(())
+    })
+}
+
+fn handle_http<F>(handle: &Reloadable<SpellChecker<F>>, stream: TcpStream) -> io::Result<()>
+where
+    F: Corrector,
+{
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next());
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![];
+    if content_length <= MAX_BODY_LEN {
+        body.resize(content_length, 0);
+        reader.read_exact(&mut body)?;
+    }
+
+    let rpc_method = path.and_then(|path| path.strip_prefix('/'));
+    let (status, response) = match (method, rpc_method) {
+        _ if content_length > MAX_BODY_LEN => (
+            "413 Payload Too Large",
+            json!({ "error": "request body too large" }),
+        ),
+        ("POST", Some(rpc_method)) if METHODS.contains(&rpc_method) => {
+            match serde_json::from_slice::<Value>(&body) {
+                Ok(mut request) if request.is_object() => {
+                    request["method"] = json!(rpc_method);
+                    ("200 OK", handle_request(&handle.snapshot(), &request))
+                }
+                _ => ("400 Bad Request", json!({ "error": "invalid request" })),
+            }
+        }
+        ("POST", _) => ("404 Not Found", json!({ "error": "unknown method" })),
+        _ => (
+            "405 Method Not Allowed",
+            json!({ "error": "only POST is supported" }),
+        ),
+    };
+
+    let body = response.to_string();
+    write!(
+        reader.get_mut(),
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    reader.get_mut().flush()
+}
+
+pub fn request_http<A: ToSocketAddrs>(addr: A, request: &Value) -> io::Result<Value> {
+    let mut stream = TcpStream::connect(addr)?;
+    let method = request["method"].as_str().unwrap_or_default();
+    let body = request.to_string();
+    write!(
+        stream,
+        "POST /{method} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (_, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "malformed HTTP response"))?;
+    serde_json::from_str(body).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    use serde_json::json;
+
+    use crate::{
+        daemon::{request_http, serve_http},
+        reload::Reloadable,
+        spell_checker::{Corrector, EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    fn spell_checker() -> Reloadable<SpellChecker<impl Corrector + Send + Sync>> {
+        Reloadable::new(|| {
+            Ok(SpellChecker::new(
+                Trie::new(&mut ["a", "donkey", "monkey", "on", "the", "roof"]),
+                |word, trie| EscalationPolicy::default().correct(word, trie),
+            ))
+        })
+        .unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket() {
+        use std::{
+            env, fs,
+            io::{BufRead, BufReader},
+            os::unix::net::{UnixListener, UnixStream},
+            process,
+        };
+
+        use crate::daemon::{MAX_BODY_LEN, request_unix, serve_unix};
+
+        let path = env::temp_dir().join(format!("lev-automaton-test-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || serve_unix(&spell_checker(), listener));
+
+        let clients: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                thread::spawn(move || {
+                    request_unix(
+                        &path,
+                        &json!({ "method": "check", "text": "a donkep on\nthe rof" }),
+                    )
+                    .unwrap()
+                })
+            })
+            .collect();
+        for client in clients {
+            let response = client.join().unwrap();
+            let misspellings = &response["misspellings"];
+            assert_eq!(misspellings.as_array().unwrap().len(), 2);
+            assert_eq!(misspellings[0]["word"], "donkep");
+            assert_eq!(misspellings[0]["suggestions"], json!(["donkey"]));
+            assert_eq!(misspellings[1]["line"], 2);
+            assert_eq!(misspellings[1]["column"], 5);
+        }
+
+        let response = request_unix(&path, &json!({ "method": "suggest", "word": "roof" }));
+        assert_eq!(response.unwrap()["correct"], true);
+        let response = request_unix(&path, &json!({ "method": "nope" })).unwrap();
+        assert_eq!(response["error"], "unknown method: nope");
+        let mut stream = UnixStream::connect(&path).unwrap();
+        let _ = stream.write_all(&vec![b'a'; MAX_BODY_LEN + 1]);
+        let mut response = String::new();
+        BufReader::new(stream).read_line(&mut response).unwrap();
+        assert_eq!(response, "{\"error\":\"request too large\"}\n");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve_http(&spell_checker(), listener));
+
+        let response = request_http(
+            addr,
+            &json!({ "method": "fuzzy-query", "word": "donkey", "distance": 1 }),
+        )
+        .unwrap();
+        assert_eq!(response["words"], json!(["donkey", "monkey"]));
+        let response = request_http(
+            addr,
+            &json!({ "method": "fuzzy-query", "word": "donkey", "distance": 1000000 }),
+        )
+        .unwrap();
+        assert_eq!(response["error"], "distance must be at most 3");
+
+        let response = request_http(addr, &json!({ "method": "suggest", "word": "monkei" }));
+        assert_eq!(
+            response.unwrap(),
+            json!({ "correct": false, "suggestions": ["monkey"] })
+        );
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST /check HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs::{self, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::trie::Trie;
+
+#[derive(Debug, Clone)]
+pub struct PersonalDictionary {
+    path: PathBuf,
+    words: BTreeSet<String>,
+    trie: Trie,
+}
+
+impl PersonalDictionary {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let words = match fs::read_to_string(path) {
+            Ok(content) => content
+                .lines()
+                .map(str::trim)
+                .filter(|w| !w.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            trie: build_trie(&words),
+            words,
+        })
+    }
+
+    pub fn add(&mut self, word: &str) -> io::Result<bool> {
+        if self.words.contains(word) {
+            return Ok(false);
+        }
+
+        Self::append(&self.path, word)?;
+        self.words.insert(word.to_string());
+        self.trie.insert(word);
+        Ok(true)
+    }
+
+    pub fn append(path: &Path, word: &str) -> io::Result<()> {
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("not a single word: {word:?}"),
+            ));
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        if file.metadata()?.len() > 0 {
+            let mut last = [0];
+            file.seek(SeekFrom::End(-1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                writeln!(file)?;
+            }
+        }
+        writeln!(file, "{word}")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DictionaryStack {
+    layers: Vec<Trie>,
+    personal: Option<PersonalDictionary>,
+    ignored: HashSet<String>,
+}
+
+impl From<Trie> for DictionaryStack {
+    fn from(trie: Trie) -> Self {
+        Self::new(trie)
+    }
+}
+
+impl DictionaryStack {
+    pub fn new(main: Trie) -> Self {
+        Self {
+            layers: vec![main],
+            personal: None,
+            ignored: HashSet::new(),
+        }
+    }
+
+    pub fn load(paths: &[PathBuf], personal: Option<&Path>) -> io::Result<Self> {
+        let mut tries = paths.iter().map(|path| Trie::load(path));
+        let main = tries.next().unwrap_or_else(|| {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no dictionary given",
+            ))
+        })?;
+
+        let mut stack = Self::new(main);
+        for trie in tries {
+            stack = stack.with_layer(trie?);
+        }
+        if let Some(path) = personal {
+            stack = stack.with_personal(PersonalDictionary::load(path)?);
+        }
+        Ok(stack)
+    }
+
+    pub fn with_layer(mut self, trie: Trie) -> Self {
+        self.layers.push(trie);
+        self
+    }
+
+    pub fn with_personal(mut self, personal: PersonalDictionary) -> Self {
+        self.personal = Some(personal);
+        self
+    }
+
+    pub fn with_ignored<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignored.extend(words.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn main(&self) -> &Trie {
+        &self.layers[0]
+    }
+
+    pub fn tries(&self) -> impl Iterator<Item = &Trie> {
+        self.layers
+            .iter()
+            .chain(self.personal.as_ref().map(PersonalDictionary::trie))
+    }
+
+    pub fn personal(&self) -> Option<&PersonalDictionary> {
+        self.personal.as_ref()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.is_ignored(word) || self.tries().any(|trie| trie.contains(word))
+    }
+
+    pub fn is_ignored(&self, word: &str) -> bool {
+        self.ignored.contains(word)
+    }
+
+    pub fn ignore(&mut self, word: &str) {
+        self.ignored.insert(word.to_string());
+    }
+
+    pub fn add_to_personal(&mut self, word: &str) -> io::Result<bool> {
+        match &mut self.personal {
+            Some(personal) => personal.add(word),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no personal dictionary configured",
+            )),
+        }
+    }
+}
+
+fn build_trie(words: &BTreeSet<String>) -> Trie {
+    Trie::new(&mut words.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, process};
+
+    use crate::{
+        dictionary::{DictionaryStack, PersonalDictionary},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_dictionary_stack() {
+        let path = env::temp_dir().join(format!("lev-automaton-personal-{}.txt", process::id()));
+        fs::write(&path, "rustacean").unwrap();
+
+        let mut stack = DictionaryStack::new(Trie::new(&mut ["the", "roof"]))
+            .with_layer(Trie::new(&mut ["automaton"]))
+            .with_personal(PersonalDictionary::load(&path).unwrap())
+            .with_ignored(["teh"]);
+
+        assert!(stack.contains("roof"));
+        assert!(stack.contains("automaton"));
+        assert!(stack.contains("rustacean"));
+        assert!(stack.contains("teh"));
+        assert!(!stack.contains("ferris"));
+        assert_eq!(stack.tries().count(), 3);
+
+        assert!(stack.add_to_personal("ferris").unwrap());
+        assert!(!stack.add_to_personal("ferris").unwrap());
+        assert!(stack.contains("ferris"));
+
+        stack.ignore("lev");
+        assert!(stack.contains("lev"));
+
+        let reloaded = PersonalDictionary::load(&path).unwrap();
+        assert!(reloaded.trie().contains("rustacean"));
+        assert!(reloaded.trie().contains("ferris"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "rustacean\nferris\n");
+
+        for word in ["", "two words", "crab\nferris", "crab\r"] {
+            assert!(stack.add_to_personal(word).is_err());
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "rustacean\nferris\n");
+
+        let missing = env::temp_dir().join(format!("lev-automaton-missing-{}", process::id()));
+        let mut unwritable = PersonalDictionary::load(&missing.join("words.txt")).unwrap();
+        assert!(unwritable.add("crab").is_err());
+        assert!(unwritable.add("crab").is_err());
+        assert!(!unwritable.trie().contains("crab"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+pub fn unified_diff(
+    old_name: &str,
+    new_name: &str,
+    old: &str,
+    new: &str,
+    context: usize,
+) -> String {
+    let old_lines: Vec<_> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<_> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let changes: Vec<_> = (0..ops.len()).filter(|&i| ops[i].0 != Op::Equal).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut hunks = vec![];
+    let mut first = changes[0];
+    let mut last = changes[0];
+    for &change in &changes[1..] {
+        if change - last > 2 * context {
+            hunks.push((first, last));
+            first = change;
+        }
+        last = change;
+    }
+    hunks.push((first, last));
+
+    let mut diff = format!("--- {old_name}\n+++ {new_name}\n");
+    for (first, last) in hunks {
+        let start = first.saturating_sub(context);
+        let end = (last + 1 + context).min(ops.len());
+
+        let old_before = ops[..start].iter().filter(|op| op.0 != Op::Insert).count();
+        let new_before = ops[..start].iter().filter(|op| op.0 != Op::Delete).count();
+        let old_count = ops[start..end]
+            .iter()
+            .filter(|op| op.0 != Op::Insert)
+            .count();
+        let new_count = ops[start..end]
+            .iter()
+            .filter(|op| op.0 != Op::Delete)
+            .count();
+        let _ = writeln!(
+            diff,
+            "@@ -{},{old_count} +{},{new_count} @@",
+            old_before + usize::from(old_count > 0),
+            new_before + usize::from(new_count > 0),
+        );
+
+        for (op, line) in &ops[start..end] {
+            let prefix = match op {
+                Op::Equal => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    diff
+}
+
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+    for i in (0..old_mid.len()).rev() {
+        for j in (0..new_mid.len()).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<_> = old[..prefix].iter().map(|l| (Op::Equal, *l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_mid.len() || j < new_mid.len() {
+        if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+            ops.push((Op::Equal, old_mid[i]));
+            i += 1;
+            j += 1;
+        } else if j == new_mid.len() || (i < old_mid.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Delete, old_mid[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, new_mid[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|l| (Op::Equal, *l)));
+    ops
+}
+
+#[cfg(test)]
+mod test {
+    use crate::diff::unified_diff;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nJ";
+
+        assert_eq!(unified_diff("a", "b", old, old, 3), "");
+        assert_eq!(
+            unified_diff("a/x", "b/x", old, new, 1),
+            [
+                "--- a/x",
+                "+++ b/x",
+                "@@ -1,3 +1,3 @@",
+                " a",
+                "-b",
+                "+B",
+                " c",
+                "@@ -9,2 +9,2 @@",
+                " i",
+                "-j",
+                "+J",
+                "\\ No newline at end of file",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            unified_diff("a", "b", "x\n", "x\ny\n", 3),
+            "--- a\n+++ b\n@@ -1,1 +1,2 @@\n x\n+y\n"
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use crate::{spell_checker::Corrector, trie::Trie};
+
+const SMOOTHING: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Match(char),
+    Insertion(char),
+    Deletion(char),
+    Substitution(char, char),
+    Transposition(char, char),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ErrorModel {
+    insertions: HashMap<char, usize>,
+    deletions: HashMap<char, usize>,
+    substitutions: HashMap<(char, char), usize>,
+    transpositions: HashMap<(char, char), usize>,
+    unigrams: HashMap<char, usize>,
+    bigrams: HashMap<(char, char), usize>,
+    char_count: usize,
+}
+
+impl ErrorModel {
+    pub fn train<'a, I>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let mut model = Self::default();
+        for (misspelled, correct) in pairs {
+            model.add_pair(misspelled, correct);
+        }
+        model
+    }
+
+    pub fn add_pair(&mut self, misspelled: &str, correct: &str) {
+        let correct_chars: Vec<char> = correct.chars().collect();
+        for c in &correct_chars {
+            *self.unigrams.entry(*c).or_default() += 1;
+        }
+        for pair in correct_chars.windows(2) {
+            *self.bigrams.entry((pair[0], pair[1])).or_default() += 1;
+        }
+        self.char_count += correct_chars.len();
+
+        for edit in align(misspelled, correct) {
+            match edit {
+                Edit::Match(_) => {}
+                Edit::Insertion(c) => *self.insertions.entry(c).or_default() += 1,
+                Edit::Deletion(c) => *self.deletions.entry(c).or_default() += 1,
+                Edit::Substitution(expected, typed) => {
+                    *self.substitutions.entry((expected, typed)).or_default() += 1
+                }
+                Edit::Transposition(a, b) => *self.transpositions.entry((a, b)).or_default() += 1,
+            }
+        }
+    }
+
+    pub fn edit_probability(&self, edit: Edit) -> f64 {
+        let alphabet = (self.unigrams.len() + 1) as f64;
+        let smoothed = |count: usize, total: usize| {
+            (count as f64 + SMOOTHING) / (total as f64 + SMOOTHING * alphabet)
+        };
+        let unigram = |c: char| self.unigrams.get(&c).copied().unwrap_or(0);
+
+        match edit {
+            Edit::Match(c) => {
+                let total = unigram(c);
+                let errors = self.deletions.get(&c).copied().unwrap_or(0)
+                    + self
+                        .substitutions
+                        .iter()
+                        .filter(|((expected, _), _)| *expected == c)
+                        .map(|(_, count)| count)
+                        .sum::<usize>();
+                smoothed(total.saturating_sub(errors), total)
+            }
+            Edit::Insertion(c) => smoothed(
+                self.insertions.get(&c).copied().unwrap_or(0),
+                self.char_count,
+            ),
+            Edit::Deletion(c) => smoothed(self.deletions.get(&c).copied().unwrap_or(0), unigram(c)),
+            Edit::Substitution(expected, typed) => smoothed(
+                self.substitutions
+                    .get(&(expected, typed))
+                    .copied()
+                    .unwrap_or(0),
+                unigram(expected),
+            ),
+            Edit::Transposition(a, b) => smoothed(
+                self.transpositions.get(&(a, b)).copied().unwrap_or(0),
+                self.bigrams.get(&(a, b)).copied().unwrap_or(0),
+            ),
+        }
+    }
+
+    pub fn log_likelihood(&self, misspelled: &str, correct: &str) -> f64 {
+        let typed: Vec<char> = misspelled.chars().collect();
+        let expected: Vec<char> = correct.chars().collect();
+        let cost = |edit| self.edit_probability(edit).ln();
+
+        let mut table = vec![vec![f64::NEG_INFINITY; typed.len() + 1]; expected.len() + 1];
+        table[0][0] = 0.0;
+
+        for i in 0..=expected.len() {
+            for j in 0..=typed.len() {
+                let mut best = table[i][j];
+                if i > 0 {
+                    best = best.max(table[i - 1][j] + cost(Edit::Deletion(expected[i - 1])));
+                }
+                if j > 0 {
+                    best = best.max(table[i][j - 1] + cost(Edit::Insertion(typed[j - 1])));
+                }
+                if i > 0 && j > 0 {
+                    let edit = if expected[i - 1] == typed[j - 1] {
+                        Edit::Match(typed[j - 1])
+                    } else {
+                        Edit::Substitution(expected[i - 1], typed[j - 1])
+                    };
+                    best = best.max(table[i - 1][j - 1] + cost(edit));
+                }
+                if i > 1
+                    && j > 1
+                    && expected[i - 1] == typed[j - 2]
+                    && expected[i - 2] == typed[j - 1]
+                    && expected[i - 1] != expected[i - 2]
+                {
+                    let edit = Edit::Transposition(expected[i - 2], expected[i - 1]);
+                    best = best.max(table[i - 2][j - 2] + cost(edit));
+                }
+                table[i][j] = best;
+            }
+        }
+
+        table[expected.len()][typed.len()]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NoisyChannel {
+    error_model: ErrorModel,
+    word_counts: HashMap<String, usize>,
+    total_count: usize,
+}
+
+impl NoisyChannel {
+    pub fn new(error_model: ErrorModel) -> Self {
+        Self {
+            error_model,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_word_counts<'a, I>(&mut self, words: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.add_word_frequencies(words.into_iter().map(|word| (word, 1)));
+    }
+
+    pub fn add_word_frequencies<'a, I>(&mut self, frequencies: I)
+    where
+        I: IntoIterator<Item = (&'a str, usize)>,
+    {
+        for (word, count) in frequencies {
+            *self.word_counts.entry(word.to_string()).or_default() += count;
+            self.total_count += count;
+        }
+    }
+
+    pub fn log_prior(&self, word: &str) -> f64 {
+        let count = self.word_counts.get(word).copied().unwrap_or(0);
+        ((count + 1) as f64 / (self.total_count + self.word_counts.len() + 1) as f64).ln()
+    }
+
+    pub fn score(&self, misspelled: &str, candidate: &str) -> f64 {
+        self.error_model.log_likelihood(misspelled, candidate) + self.log_prior(candidate)
+    }
+
+    pub fn rank(&self, misspelled: &str, candidates: Vec<String>) -> Vec<String> {
+        let mut scored: Vec<(f64, String)> = candidates
+            .into_iter()
+            .map(|candidate| (self.score(misspelled, &candidate), candidate))
+            .collect();
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            b_score.total_cmp(a_score).then_with(|| a.cmp(b))
+        });
+        scored.dedup_by(|(_, a), (_, b)| a == b);
+        scored.into_iter().map(|(_, candidate)| candidate).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChannelCorrector<C> {
+    corrector: C,
+    channel: NoisyChannel,
+}
+
+impl<C> ChannelCorrector<C>
+where
+    C: Corrector,
+{
+    pub fn new(corrector: C, channel: NoisyChannel) -> Self {
+        Self { corrector, channel }
+    }
+}
+
+impl<C> Corrector for ChannelCorrector<C>
+where
+    C: Corrector,
+{
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        self.corrector.candidates(word, tries)
+    }
+
+    fn score(&self, word: &str, candidate: &str) -> f64 {
+        -self.channel.score(word, candidate)
+    }
+}
+
+pub fn parse_frequencies(text: &str) -> impl Iterator<Item = (&str, usize)> {
+    text.lines().filter_map(|line| {
+        let mut fields = line.split_whitespace();
+        let word = fields.next()?;
+        let count = fields.next().map_or(Some(1), |count| count.parse().ok())?;
+        Some((word, count))
+    })
+}
+
+pub fn align(misspelled: &str, correct: &str) -> Vec<Edit> {
+    let typed: Vec<char> = misspelled.chars().collect();
+    let expected: Vec<char> = correct.chars().collect();
+
+    let mut table = vec![vec![0usize; typed.len() + 1]; expected.len() + 1];
+    for (i, row) in table.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    table[0] = (0..=typed.len()).collect();
+
+    for i in 1..=expected.len() {
+        for j in 1..=typed.len() {
+            let substitution = table[i - 1][j - 1] + usize::from(expected[i - 1] != typed[j - 1]);
+            let mut best = substitution
+                .min(table[i - 1][j] + 1)
+                .min(table[i][j - 1] + 1);
+            if i > 1
+                && j > 1
+                && expected[i - 1] == typed[j - 2]
+                && expected[i - 2] == typed[j - 1]
+                && expected[i - 1] != expected[i - 2]
+            {
+                best = best.min(table[i - 2][j - 2] + 1);
+            }
+            table[i][j] = best;
+        }
+    }
+
+    let mut edits = vec![];
+    let (mut i, mut j) = (expected.len(), typed.len());
+    while i > 0 || j > 0 {
+        if i > 0
+            && j > 0
+            && table[i][j] == table[i - 1][j - 1] + usize::from(expected[i - 1] != typed[j - 1])
+        {
+            edits.push(if expected[i - 1] == typed[j - 1] {
+                Edit::Match(typed[j - 1])
+            } else {
+                Edit::Substitution(expected[i - 1], typed[j - 1])
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 1
+            && j > 1
+            && expected[i - 1] == typed[j - 2]
+            && expected[i - 2] == typed[j - 1]
+            && expected[i - 1] != expected[i - 2]
+            && table[i][j] == table[i - 2][j - 2] + 1
+        {
+            edits.push(Edit::Transposition(expected[i - 2], expected[i - 1]));
+            i -= 2;
+            j -= 2;
+        } else if i > 0 && table[i][j] == table[i - 1][j] + 1 {
+            edits.push(Edit::Deletion(expected[i - 1]));
+            i -= 1;
+        } else {
+            edits.push(Edit::Insertion(typed[j - 1]));
+            j -= 1;
+        }
+    }
+
+    edits.reverse();
+    edits
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error_model::{ChannelCorrector, Edit, ErrorModel, NoisyChannel, align, parse_frequencies},
+        spell_checker::{EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_align() {
+        assert_eq!(
+            align("teh", "the"),
+            vec![Edit::Match('t'), Edit::Transposition('h', 'e')]
+        );
+        assert_eq!(
+            align("sloger", "slogger"),
+            vec![
+                Edit::Match('s'),
+                Edit::Match('l'),
+                Edit::Match('o'),
+                Edit::Deletion('g'),
+                Edit::Match('g'),
+                Edit::Match('e'),
+                Edit::Match('r'),
+            ]
+        );
+        assert_eq!(
+            align("wwin", "win"),
+            vec![
+                Edit::Insertion('w'),
+                Edit::Match('w'),
+                Edit::Match('i'),
+                Edit::Match('n'),
+            ]
+        );
+        assert_eq!(
+            align("cst", "cat"),
+            vec![
+                Edit::Match('c'),
+                Edit::Substitution('a', 's'),
+                Edit::Match('t'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trained_ranking() {
+        let model = ErrorModel::train([
+            ("recieve", "receive"),
+            ("beleive", "believe"),
+            ("peice", "piece"),
+            ("cst", "cat"),
+        ]);
+
+        assert!(
+            model.edit_probability(Edit::Transposition('e', 'i'))
+                > model.edit_probability(Edit::Transposition('r', 'o'))
+        );
+        assert!(
+            model.edit_probability(Edit::Substitution('a', 's'))
+                > model.edit_probability(Edit::Substitution('a', 'q'))
+        );
+
+        let channel = NoisyChannel::new(model);
+        let candidates = vec!["reprieve".to_string(), "receive".to_string()];
+        assert_eq!(channel.rank("recieve", candidates)[0], "receive");
+
+        let mut channel = channel;
+        channel.add_word_counts(["cut"]);
+        let candidates = vec!["cat".to_string(), "cut".to_string()];
+        assert_eq!(channel.rank("cst", candidates.clone()), vec!["cat", "cut"]);
+        channel.add_word_counts(["cut"; 100]);
+        assert_eq!(channel.rank("cst", candidates.clone()), vec!["cut", "cat"]);
+        channel.add_word_frequencies([("cat", 1000)]);
+        assert_eq!(
+            channel.rank("cst", [candidates.clone(), candidates].concat()),
+            vec!["cat", "cut"]
+        );
+    }
+
+    #[test]
+    fn test_channel_corrector() {
+        let words = || Trie::new(&mut ["cat", "cut", "the"]);
+        let plain = SpellChecker::from_corrector(words(), EscalationPolicy::default());
+        assert_eq!(
+            plain.check_word("cst"),
+            Err(vec!["cat".into(), "cut".into()])
+        );
+
+        let mut channel = NoisyChannel::new(ErrorModel::train([("cst", "cat")]));
+        channel.add_word_frequencies(parse_frequencies("cut 1000\ncat 1\nthe\n"));
+        let ranked = SpellChecker::from_corrector(
+            words(),
+            ChannelCorrector::new(EscalationPolicy::default(), channel),
+        );
+        assert_eq!(
+            ranked.check_word("cst"),
+            Err(vec!["cut".into(), "cat".into()])
+        );
+    }
+}
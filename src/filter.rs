@@ -0,0 +1,394 @@
+use std::{
+    io::{self, ErrorKind},
+    ops::Range,
+    path::Path,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Filter {
+    #[default]
+    Plain,
+    Markdown,
+    Html,
+    Source(Syntax),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    CLike,
+    Hash,
+}
+
+impl Syntax {
+    fn line_comment(&self) -> &'static str {
+        match self {
+            Syntax::CLike => "//",
+            Syntax::Hash => "#",
+        }
+    }
+
+    fn block_comment(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Syntax::CLike => Some(("/*", "*/")),
+            Syntax::Hash => None,
+        }
+    }
+
+    fn quotes(&self) -> &'static [u8] {
+        match self {
+            Syntax::CLike => b"\"",
+            Syntax::Hash => b"\"'",
+        }
+    }
+}
+
+impl Filter {
+    pub fn for_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "md" | "markdown" | "mdx" => Filter::Markdown,
+            "html" | "htm" | "xhtml" | "xml" | "svg" => Filter::Html,
+            "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "js" | "jsx" | "ts" | "tsx"
+            | "go" | "swift" | "kt" | "cs" | "scala" | "dart" => Filter::Source(Syntax::CLike),
+            "py" | "sh" | "bash" | "rb" | "pl" | "toml" | "yaml" | "yml" | "r" => {
+                Filter::Source(Syntax::Hash)
+            }
+            _ => Filter::Plain,
+        }
+    }
+
+    pub fn mask(&self, text: &str) -> io::Result<String> {
+        let bytes = match self {
+            Filter::Plain => return Ok(text.to_string()),
+            Filter::Markdown => mask_markdown(text),
+            Filter::Html => mask_html(text),
+            Filter::Source(syntax) => mask_source(text, syntax),
+        };
+        String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+}
+
+fn blank(out: &mut [u8], range: Range<usize>) {
+    for byte in &mut out[range] {
+        if *byte != b'\n' {
+            *byte = b' ';
+        }
+    }
+}
+
+fn mask_markdown(text: &str) -> Vec<u8> {
+    let mut out = text.as_bytes().to_vec();
+    let mut fence: Option<(u8, usize)> = None;
+    let mut line_start = 0;
+
+    for line in text.split_inclusive('\n') {
+        let range = line_start..line_start + line.len();
+        line_start = range.end;
+
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.bytes().next().unwrap_or_default();
+        let run = trimmed.bytes().take_while(|b| *b == marker).count();
+
+        if let Some((fence_marker, fence_len)) = fence {
+            if marker == fence_marker && run >= fence_len && trimmed[run..].trim().is_empty() {
+                fence = None;
+            }
+            blank(&mut out, range);
+            continue;
+        }
+        if indent <= 3 && (marker == b'`' || marker == b'~') && run >= 3 {
+            fence = Some((marker, run));
+            blank(&mut out, range);
+            continue;
+        }
+
+        if indent <= 3
+            && marker == b'['
+            && let Some(idx) = trimmed.find("]:")
+        {
+            let url_start = range.start + indent + idx + 2;
+            blank(&mut out, url_start..range.end);
+        }
+        mask_markdown_inline(text, range, &mut out);
+    }
+
+    out
+}
+
+fn mask_markdown_inline(text: &str, range: Range<usize>, out: &mut [u8]) {
+    let bytes = text.as_bytes();
+    let mut i = range.start;
+
+    while i < range.end {
+        match bytes[i] {
+            b'`' => {
+                let run = bytes[i..range.end]
+                    .iter()
+                    .take_while(|b| **b == b'`')
+                    .count();
+                let closing = (i + run..range.end).find(|&j| {
+                    bytes[j..range.end]
+                        .iter()
+                        .take_while(|b| **b == b'`')
+                        .count()
+                        == run
+                        && bytes[j - 1] != b'`'
+                });
+                match closing {
+                    Some(j) => {
+                        blank(out, i..j + run);
+                        i = j + run;
+                    }
+                    None => i += run,
+                }
+            }
+            b']' if bytes.get(i + 1) == Some(&b'(') => {
+                let end = text[i..range.end]
+                    .find(')')
+                    .map_or(range.end, |idx| i + idx + 1);
+                blank(out, i + 1..end);
+                i = end;
+            }
+            b'<' if bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || b"/!".contains(b)) =>
+            {
+                match text[i..range.end].find('>') {
+                    Some(idx) => {
+                        blank(out, i..i + idx + 1);
+                        i += idx + 1;
+                    }
+                    None => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+fn mask_html(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = bytes.to_vec();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'<' if text[i..].starts_with("<!--") => {
+                let end = text[i..].find("-->").map_or(text.len(), |idx| i + idx + 3);
+                blank(&mut out, i..end);
+                i = end;
+            }
+            b'<' if bytes
+                .get(i + 1)
+                .is_some_and(|b| b.is_ascii_alphabetic() || b"/!?".contains(b)) =>
+            {
+                let end = tag_end(bytes, i);
+                blank(&mut out, i..end);
+
+                let name: String = text[i + 1..end]
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase();
+                i = end;
+                if name == "script" || name == "style" {
+                    let closing = format!("</{name}");
+                    let content_end = text[i..]
+                        .to_ascii_lowercase()
+                        .find(&closing)
+                        .map_or(text.len(), |idx| i + idx);
+                    blank(&mut out, i..content_end);
+                    i = content_end;
+                }
+            }
+            b'&' => {
+                let entity_len = bytes[i + 1..]
+                    .iter()
+                    .take(10)
+                    .position(|b| !(b.is_ascii_alphanumeric() || *b == b'#'));
+                match entity_len {
+                    Some(len) if len > 0 && bytes[i + 1 + len] == b';' => {
+                        blank(&mut out, i..i + len + 2);
+                        i += len + 2;
+                    }
+                    _ => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    out
+}
+
+fn tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+    for (i, byte) in bytes.iter().enumerate().skip(start + 1) {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(*byte),
+            (Some(q), b) if q == *b => quote = None,
+            (None, b'>') => return i + 1,
+            _ => {}
+        }
+    }
+    bytes.len()
+}
+
+fn mask_source(text: &str, syntax: &Syntax) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = bytes.to_vec();
+    blank(&mut out, 0..bytes.len());
+
+    let keep = |out: &mut Vec<u8>, range: Range<usize>| {
+        out[range.clone()].copy_from_slice(&bytes[range]);
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &text[i..];
+
+        if rest.starts_with(syntax.line_comment()) {
+            let start = i + syntax.line_comment().len();
+            let end = text[start..]
+                .find('\n')
+                .map_or(text.len(), |idx| start + idx);
+            keep(&mut out, start..end);
+            i = end;
+        } else if let Some((open, close)) = syntax.block_comment()
+            && rest.starts_with(open)
+        {
+            let start = i + open.len();
+            let end = text[start..]
+                .find(close)
+                .map_or(text.len(), |idx| start + idx);
+            keep(&mut out, start..end);
+            i = (end + close.len()).min(text.len());
+        } else if *syntax == Syntax::CLike
+            && bytes[i] == b'\''
+            && let Some(len) = char_literal_len(rest)
+        {
+            i += len;
+        } else if syntax.quotes().contains(&bytes[i]) {
+            let quote = bytes[i];
+            i += 1;
+            let mut start = i;
+            while i < bytes.len() && bytes[i] != quote {
+                if bytes[i] == b'\\' {
+                    keep(&mut out, start..i);
+                    let escaped_len = text[i + 1..].chars().next().map_or(0, char::len_utf8);
+                    i += 1 + escaped_len;
+                    start = i;
+                } else {
+                    i += 1;
+                }
+            }
+            let end = i.min(bytes.len());
+            keep(&mut out, start..end);
+            i = end + 1;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    out
+}
+
+fn char_literal_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    match chars.next()? {
+        (_, '\\') => chars
+            .skip(1)
+            .take(10)
+            .find(|(_, c)| *c == '\'')
+            .map(|(idx, _)| idx + 1),
+        (_, '\'') => None,
+        _ => chars
+            .next()
+            .filter(|(_, c)| *c == '\'')
+            .map(|(idx, _)| idx + 1),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::filter::{Filter, Syntax};
+
+    #[test]
+    fn test_markdown() {
+        let text = "Use `lev --hlep` to [see hlep](https://exmaple.com).\n\
+                    ```rust\nlet x = bogus;\n```\n\
+                    Some <b>bold</b> text ``a ` b``.\n";
+        let masked = Filter::Markdown.mask(text).unwrap();
+
+        assert_eq!(masked.len(), text.len());
+        assert_eq!(masked.find("hlep]"), text.find("hlep]"));
+        let words: Vec<_> = masked.split_whitespace().collect();
+        assert_eq!(
+            words,
+            vec![
+                "Use", "to", "[see", "hlep]", ".", "Some", "bold", "text", "."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let text = "<p class=\"a>b\">Héllo &amp; wrld</p><!-- nope -->\
+                    <script>var x;</script>";
+        let masked = Filter::Html.mask(text).unwrap();
+
+        assert_eq!(masked.len(), text.len());
+        let words: Vec<_> = masked.split_whitespace().collect();
+        assert_eq!(words, vec!["Héllo", "wrld"]);
+        assert_eq!(masked.find("Héllo"), text.find("Héllo"));
+    }
+
+    #[test]
+    fn test_source() {
+        let text = "fn main() { // say helo\n    println!(\"Hi\\nthere, wrld\"); /* blok */\n}\n";
+        let masked = Filter::Source(Syntax::CLike).mask(text).unwrap();
+
+        assert_eq!(masked.len(), text.len());
+        assert_eq!(masked.find("helo"), text.find("helo"));
+        let words: Vec<_> = masked.split_whitespace().collect();
+        assert_eq!(words, vec!["say", "helo", "Hi", "there,", "wrld", "blok"]);
+
+        let text = "if c == '\"' || c == '\\'' { s = \"qoute\" } // 'a lifetyme\n";
+        let masked = Filter::Source(Syntax::CLike).mask(text).unwrap();
+        let words: Vec<_> = masked.split_whitespace().collect();
+        assert_eq!(words, vec!["qoute", "'a", "lifetyme"]);
+
+        let masked = Filter::Source(Syntax::Hash)
+            .mask("x = 'teh' # commnt\n")
+            .unwrap();
+        assert_eq!(masked, "     teh    commnt\n");
+
+        let text = "let π = \"ünïcode strng\"; // commnt\n";
+        let masked = Filter::Source(Syntax::CLike).mask(text).unwrap();
+        assert_eq!(masked.len(), text.len());
+        let words: Vec<_> = masked.split_whitespace().collect();
+        assert_eq!(words, vec!["ünïcode", "strng", "commnt"]);
+        let masked = Filter::Source(Syntax::Hash)
+            .mask("ä = 'wörd' # x\n")
+            .unwrap();
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec!["wörd", "x"]
+        );
+
+        assert_eq!(Filter::for_path(Path::new("README.md")), Filter::Markdown);
+        assert_eq!(
+            Filter::for_path(Path::new("src/main.rs")),
+            Filter::Source(Syntax::CLike)
+        );
+        assert_eq!(Filter::for_path(Path::new("notes")), Filter::Plain);
+    }
+}
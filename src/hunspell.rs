@@ -0,0 +1,673 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use crate::trie::Trie;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    Short,
+    Long,
+    Numeric,
+    Utf8,
+}
+
+impl FlagType {
+    fn parse(&self, flags: &str) -> Vec<String> {
+        match self {
+            Self::Short | Self::Utf8 => flags.chars().map(String::from).collect(),
+            Self::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|chunk| chunk.iter().collect())
+                .collect(),
+            Self::Numeric => flags
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Debug, Clone)]
+enum CharClass {
+    Any,
+    Is(char),
+    OneOf(HashSet<char>),
+    NoneOf(HashSet<char>),
+}
+
+impl CharClass {
+    fn allows(&self, c: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Is(cc) => c == *cc,
+            Self::OneOf(cs) => cs.contains(&c),
+            Self::NoneOf(cs) => !cs.contains(&c),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AffixRule {
+    strip: String,
+    affix: String,
+    continuation: Vec<String>,
+    condition: Vec<CharClass>,
+}
+
+#[derive(Debug, Clone)]
+struct AffixGroup {
+    kind: AffixKind,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AffixFile {
+    flag_type: FlagType,
+    aliases: Vec<Vec<String>>,
+    groups: HashMap<String, AffixGroup>,
+    need_affix: Option<String>,
+    only_in_compound: Option<String>,
+}
+
+impl AffixFile {
+    pub fn parse(aff: &str) -> io::Result<Self> {
+        let mut aff_file = Self {
+            flag_type: FlagType::Short,
+            aliases: vec![],
+            groups: HashMap::new(),
+            need_affix: None,
+            only_in_compound: None,
+        };
+
+        let mut lines = aff
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .filter(|fields| !fields.is_empty() && !fields[0].starts_with('#'));
+
+        while let Some(fields) = lines.next() {
+            match fields[..] {
+                ["FLAG", flag_type, ..] => {
+                    aff_file.flag_type = match flag_type {
+                        "long" => FlagType::Long,
+                        "num" => FlagType::Numeric,
+                        "UTF-8" => FlagType::Utf8,
+                        _ => return Err(invalid_data(format!("unknown flag type {flag_type}"))),
+                    }
+                }
+                ["AF", count, ..] if count.parse::<usize>().is_ok() => {
+                    for _ in 0..count.parse::<usize>().unwrap_or(0) {
+                        let alias = lines
+                            .next()
+                            .ok_or_else(|| invalid_data("missing AF line".into()))?;
+                        let flags = alias.get(1).copied().unwrap_or_default();
+                        aff_file.aliases.push(aff_file.flag_type.parse(flags));
+                    }
+                }
+                ["NEEDAFFIX", flag, ..] | ["PSEUDOROOT", flag, ..] => {
+                    aff_file.need_affix = Some(flag.to_string())
+                }
+                ["ONLYINCOMPOUND", flag, ..] => aff_file.only_in_compound = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count, ..] => {
+                    let count: usize = count
+                        .parse()
+                        .map_err(|_| invalid_data(format!("invalid {kind} count {count}")))?;
+                    let mut group = AffixGroup {
+                        kind: if kind == "PFX" {
+                            AffixKind::Prefix
+                        } else {
+                            AffixKind::Suffix
+                        },
+                        cross_product: cross_product == "Y",
+                        rules: vec![],
+                    };
+
+                    let mut dropped_continuation = false;
+                    for _ in 0..count {
+                        let rule = lines
+                            .next()
+                            .ok_or_else(|| invalid_data(format!("missing {kind} {flag} rule")))?;
+                        let [_, _, strip, affix, rest @ ..] = &rule[..] else {
+                            return Err(invalid_data(format!("invalid {kind} {flag} rule")));
+                        };
+                        let (affix, continuation) = match affix.split_once('/') {
+                            Some((affix, flags)) => (affix, aff_file.parse_flags(flags)),
+                            None => (*affix, vec![]),
+                        };
+                        dropped_continuation |=
+                            group.kind == AffixKind::Prefix && !continuation.is_empty();
+                        group.rules.push(AffixRule {
+                            strip: if *strip == "0" { "" } else { strip }.to_string(),
+                            affix: if affix == "0" { "" } else { affix }.to_string(),
+                            continuation,
+                            condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                        });
+                    }
+
+                    if dropped_continuation {
+                        eprintln!(
+                            "lev-automaton: ignoring continuation flags on prefix {flag}, \
+                             only suffixes can be chained"
+                        );
+                    }
+                    aff_file.groups.insert(flag.to_string(), group);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(aff_file)
+    }
+
+    fn parse_flags(&self, flags: &str) -> Vec<String> {
+        if !self.aliases.is_empty()
+            && let Ok(idx) = flags.parse::<usize>()
+        {
+            return self
+                .aliases
+                .get(idx.wrapping_sub(1))
+                .cloned()
+                .unwrap_or_default();
+        }
+        self.flag_type.parse(flags)
+    }
+
+    fn apply(
+        &self,
+        kind: AffixKind,
+        word: &str,
+        flags: &[String],
+    ) -> Vec<(String, bool, Vec<String>)> {
+        flags
+            .iter()
+            .filter_map(|flag| self.groups.get(flag))
+            .filter(|group| group.kind == kind)
+            .flat_map(|group| {
+                group.rules.iter().filter_map(move |rule| {
+                    rule.apply(kind, word)
+                        .map(|w| (w, group.cross_product, rule.continuation.clone()))
+                })
+            })
+            .collect()
+    }
+
+    pub fn expand(&self, stem: &str, flags: &[String]) -> Vec<String> {
+        let is_flagged = |flag: &Option<String>| flag.as_ref().is_some_and(|f| flags.contains(f));
+        if is_flagged(&self.only_in_compound) {
+            return vec![];
+        }
+
+        let mut words = vec![];
+        if !is_flagged(&self.need_affix) {
+            words.push(stem.to_string());
+        }
+
+        let suffixed = self.apply(AffixKind::Suffix, stem, flags);
+        for (word, _, continuation) in &suffixed {
+            words.push(word.clone());
+            for (word, _, _) in self.apply(AffixKind::Suffix, word, continuation) {
+                words.push(word);
+            }
+        }
+
+        for (word, _, _) in self.apply(AffixKind::Prefix, stem, flags) {
+            words.push(word);
+        }
+        for (word, suffix_cross_product, _) in &suffixed {
+            if !suffix_cross_product {
+                continue;
+            }
+            for (word, prefix_cross_product, _) in self.apply(AffixKind::Prefix, word, flags) {
+                if prefix_cross_product {
+                    words.push(word);
+                }
+            }
+        }
+
+        words
+    }
+}
+
+impl AffixRule {
+    fn apply(&self, kind: AffixKind, word: &str) -> Option<String> {
+        let chars: Vec<char> = word.chars().collect();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+
+        match kind {
+            AffixKind::Prefix => {
+                let matches = self
+                    .condition
+                    .iter()
+                    .zip(&chars)
+                    .all(|(cc, c)| cc.allows(*c));
+                let rest = word.strip_prefix(self.strip.as_str())?;
+                matches.then(|| format!("{}{rest}", self.affix))
+            }
+            AffixKind::Suffix => {
+                let tail = &chars[chars.len() - self.condition.len()..];
+                let matches = self.condition.iter().zip(tail).all(|(cc, c)| cc.allows(*c));
+                let rest = word.strip_suffix(self.strip.as_str())?;
+                matches.then(|| format!("{rest}{}", self.affix))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hunspell {
+    words: Vec<String>,
+}
+
+impl Hunspell {
+    pub fn load(dic_path: &Path, aff_path: &Path) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(dic_path)?, &fs::read(aff_path)?)
+    }
+
+    pub fn from_bytes(dic: &[u8], aff: &[u8]) -> io::Result<Self> {
+        let encoding = aff
+            .split(|b| *b == b'\n')
+            .map(|line| String::from_utf8_lossy(line))
+            .find_map(|line| {
+                line.trim_start_matches('\u{feff}')
+                    .strip_prefix("SET ")
+                    .map(|e| e.trim().to_string())
+            })
+            .unwrap_or("ISO8859-1".into());
+
+        let aff_file = AffixFile::parse(&decode(aff, &encoding)?)?;
+        let dic = decode(dic, &encoding)?;
+
+        let mut words = vec![];
+        for line in dic.lines().skip(1) {
+            let entry = line
+                .split(['\t', ' '])
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('\r');
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (stem, flags) = split_entry(entry);
+            let flags = flags.map_or(vec![], |flags| aff_file.parse_flags(flags));
+            words.extend(aff_file.expand(&stem, &flags));
+        }
+
+        words.sort();
+        words.dedup();
+        Ok(Self { words })
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn to_trie(&self) -> Trie {
+        Trie::new(&mut self.words.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+}
+
+fn split_entry(entry: &str) -> (String, Option<&str>) {
+    let mut escaped = false;
+    for (i, c) in entry.char_indices() {
+        match c {
+            '/' if !escaped && i > 0 => {
+                return (entry[..i].replace("\\/", "/"), Some(&entry[i + 1..]));
+            }
+            '\\' => escaped = !escaped,
+            _ => escaped = false,
+        }
+    }
+    (entry.replace("\\/", "/"), None)
+}
+
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+    let mut classes = vec![];
+    let mut chars = condition.chars();
+
+    while let Some(c) = chars.next() {
+        classes.push(match c {
+            '.' => CharClass::Any,
+            '[' => {
+                let mut set = HashSet::new();
+                let mut negated = false;
+                for (i, c) in chars.by_ref().enumerate() {
+                    match c {
+                        '^' if i == 0 => negated = true,
+                        ']' => break,
+                        c => {
+                            set.insert(c);
+                        }
+                    }
+                }
+                if negated {
+                    CharClass::NoneOf(set)
+                } else {
+                    CharClass::OneOf(set)
+                }
+            }
+            c => CharClass::Is(c),
+        });
+    }
+
+    classes
+}
+
+fn decode(bytes: &[u8], encoding: &str) -> io::Result<String> {
+    let (first, table) = match encoding.to_uppercase().replace('_', "-").as_str() {
+        "UTF-8" | "UTF8" => {
+            let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+            return String::from_utf8(bytes.to_vec()).map_err(|e| invalid_data(e.to_string()));
+        }
+        "ISO8859-1" | "ISO-8859-1" | "LATIN1" => {
+            return Ok(bytes.iter().map(|b| char::from(*b)).collect());
+        }
+        "ISO8859-15" | "ISO-8859-15" | "LATIN9" => {
+            return Ok(bytes
+                .iter()
+                .map(|b| match b {
+                    0xA4 => '€',
+                    0xA6 => 'Š',
+                    0xA8 => 'š',
+                    0xB4 => 'Ž',
+                    0xB8 => 'ž',
+                    0xBC => 'Œ',
+                    0xBD => 'œ',
+                    0xBE => 'Ÿ',
+                    b => char::from(*b),
+                })
+                .collect());
+        }
+        "ISO8859-2" | "ISO-8859-2" | "LATIN2" => (0xA0, ISO8859_2),
+        "ISO8859-3" | "ISO-8859-3" | "LATIN3" => (0xA0, ISO8859_3),
+        "ISO8859-4" | "ISO-8859-4" | "LATIN4" => (0xA0, ISO8859_4),
+        "ISO8859-5" | "ISO-8859-5" => (0xA0, ISO8859_5),
+        "ISO8859-6" | "ISO-8859-6" => (0xA0, ISO8859_6),
+        "ISO8859-7" | "ISO-8859-7" => (0xA0, ISO8859_7),
+        "ISO8859-9" | "ISO-8859-9" | "LATIN5" => (0xA0, ISO8859_9),
+        "ISO8859-10" | "ISO-8859-10" | "LATIN6" => (0xA0, ISO8859_10),
+        "ISO8859-13" | "ISO-8859-13" | "LATIN7" => (0xA0, ISO8859_13),
+        "ISO8859-14" | "ISO-8859-14" | "LATIN8" => (0xA0, ISO8859_14),
+        "KOI8-R" => (0x80, KOI8_R),
+        "KOI8-U" => (0x80, KOI8_U),
+        "MICROSOFT-CP1250" | "CP1250" | "WINDOWS-1250" => (0x80, CP1250),
+        "MICROSOFT-CP1251" | "CP1251" | "WINDOWS-1251" => (0x80, CP1251),
+        "TIS620-2533" | "TIS620" | "TIS-620" => (0x80, TIS620),
+        _ => return Err(invalid_data(format!("unsupported encoding {encoding}"))),
+    };
+
+    let table: Vec<char> = table.chars().collect();
+    debug_assert_eq!(table.len(), 0x100 - usize::from(first));
+    Ok(bytes
+        .iter()
+        .map(|&b| match b.checked_sub(first) {
+            Some(idx) => table[usize::from(idx)],
+            None => char::from(b),
+        })
+        .collect())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+const ISO8859_2: &str = concat!(
+    "\u{a0}Ą˘Ł¤ĽŚ§¨ŠŞŤŹ\u{ad}ŽŻ",
+    "°ą˛ł´ľśˇ¸šşťź˝žż",
+    "ŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎ",
+    "ĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢß",
+    "ŕáâăäĺćçčéęëěíîď",
+    "đńňóôőö÷řůúűüýţ˙",
+);
+
+const ISO8859_3: &str = concat!(
+    "\u{a0}Ħ˘£¤\u{fffd}Ĥ§¨İŞĞĴ\u{ad}\u{fffd}Ż",
+    "°ħ²³´µĥ·¸ışğĵ½\u{fffd}ż",
+    "ÀÁÂ\u{fffd}ÄĊĈÇÈÉÊËÌÍÎÏ",
+    "\u{fffd}ÑÒÓÔĠÖ×ĜÙÚÛÜŬŜß",
+    "àáâ\u{fffd}äċĉçèéêëìíîï",
+    "\u{fffd}ñòóôġö÷ĝùúûüŭŝ˙",
+);
+
+const ISO8859_4: &str = concat!(
+    "\u{a0}ĄĸŖ¤ĨĻ§¨ŠĒĢŦ\u{ad}Ž¯",
+    "°ą˛ŗ´ĩļˇ¸šēģŧŊžŋ",
+    "ĀÁÂÃÄÅÆĮČÉĘËĖÍÎĪ",
+    "ĐŅŌĶÔÕÖ×ØŲÚÛÜŨŪß",
+    "āáâãäåæįčéęëėíîī",
+    "đņōķôõö÷øųúûüũū˙",
+);
+
+const ISO8859_5: &str = concat!(
+    "\u{a0}ЁЂЃЄЅІЇЈЉЊЋЌ\u{ad}ЎЏ",
+    "АБВГДЕЖЗИЙКЛМНОП",
+    "РСТУФХЦЧШЩЪЫЬЭЮЯ",
+    "абвгдежзийклмноп",
+    "рстуфхцчшщъыьэюя",
+    "№ёђѓєѕіїјљњћќ§ўџ",
+);
+
+const ISO8859_6: &str = concat!(
+    "\u{a0}\u{fffd}\u{fffd}\u{fffd}¤\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}،\u{ad}\u{fffd}\u{fffd}",
+    "\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}؛\u{fffd}\u{fffd}\u{fffd}؟",
+    "\u{fffd}ءآأؤإئابةتثجحخد",
+    "ذرزسشصضطظعغ\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}",
+    "ـفقكلمنهوىي\u{64b}\u{64c}\u{64d}\u{64e}\u{64f}",
+    "\u{650}\u{651}\u{652}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}\u{fffd}",
+);
+
+const ISO8859_7: &str = concat!(
+    "\u{a0}‘’£€₯¦§¨©ͺ«¬\u{ad}\u{fffd}―",
+    "°±²³΄΅Ά·ΈΉΊ»Ό½ΎΏ",
+    "ΐΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟ",
+    "ΠΡ\u{fffd}ΣΤΥΦΧΨΩΪΫάέήί",
+    "ΰαβγδεζηθικλμνξο",
+    "πρςστυφχψωϊϋόύώ\u{fffd}",
+);
+
+const ISO8859_9: &str = concat!(
+    "\u{a0}¡¢£¤¥¦§¨©ª«¬\u{ad}®¯",
+    "°±²³´µ¶·¸¹º»¼½¾¿",
+    "ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏ",
+    "ĞÑÒÓÔÕÖ×ØÙÚÛÜİŞß",
+    "àáâãäåæçèéêëìíîï",
+    "ğñòóôõö÷øùúûüışÿ",
+);
+
+const ISO8859_10: &str = concat!(
+    "\u{a0}ĄĒĢĪĨĶ§ĻĐŠŦŽ\u{ad}ŪŊ",
+    "°ąēģīĩķ·ļđšŧž―ūŋ",
+    "ĀÁÂÃÄÅÆĮČÉĘËĖÍÎÏ",
+    "ÐŅŌÓÔÕÖŨØŲÚÛÜÝÞß",
+    "āáâãäåæįčéęëėíîï",
+    "ðņōóôõöũøųúûüýþĸ",
+);
+
+const ISO8859_13: &str = concat!(
+    "\u{a0}”¢£¤„¦§Ø©Ŗ«¬\u{ad}®Æ",
+    "°±²³“µ¶·ø¹ŗ»¼½¾æ",
+    "ĄĮĀĆÄÅĘĒČÉŹĖĢĶĪĻ",
+    "ŠŃŅÓŌÕÖ×ŲŁŚŪÜŻŽß",
+    "ąįāćäåęēčéźėģķīļ",
+    "šńņóōõö÷ųłśūüżž’",
+);
+
+const ISO8859_14: &str = concat!(
+    "\u{a0}Ḃḃ£ĊċḊ§Ẁ©ẂḋỲ\u{ad}®Ÿ",
+    "ḞḟĠġṀṁ¶ṖẁṗẃṠỳẄẅṡ",
+    "ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏ",
+    "ŴÑÒÓÔÕÖṪØÙÚÛÜÝŶß",
+    "àáâãäåæçèéêëìíîï",
+    "ŵñòóôõöṫøùúûüýŷÿ",
+);
+
+const KOI8_R: &str = concat!(
+    "─│┌┐└┘├┤┬┴┼▀▄█▌▐",
+    "░▒▓⌠■∙√≈≤≥\u{a0}⌡°²·÷",
+    "═║╒ё╓╔╕╖╗╘╙╚╛╜╝╞",
+    "╟╠╡Ё╢╣╤╥╦╧╨╩╪╫╬©",
+    "юабцдефгхийклмно",
+    "пярстужвьызшэщчъ",
+    "ЮАБЦДЕФГХИЙКЛМНО",
+    "ПЯРСТУЖВЬЫЗШЭЩЧЪ",
+);
+
+const KOI8_U: &str = concat!(
+    "─│┌┐└┘├┤┬┴┼▀▄█▌▐",
+    "░▒▓⌠■∙√≈≤≥\u{a0}⌡°²·÷",
+    "═║╒ёє╔ії╗╘╙╚╛ґ╝╞",
+    "╟╠╡ЁЄ╣ІЇ╦╧╨╩╪Ґ╬©",
+    "юабцдефгхийклмно",
+    "пярстужвьызшэщчъ",
+    "ЮАБЦДЕФГХИЙКЛМНО",
+    "ПЯРСТУЖВЬЫЗШЭЩЧЪ",
+);
+
+const CP1250: &str = concat!(
+    "€\u{fffd}‚\u{fffd}„…†‡\u{fffd}‰Š‹ŚŤŽŹ",
+    "\u{fffd}‘’“”•–—\u{fffd}™š›śťžź",
+    "\u{a0}ˇ˘Ł¤Ą¦§¨©Ş«¬\u{ad}®Ż",
+    "°±˛ł´µ¶·¸ąş»Ľ˝ľż",
+    "ŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎ",
+    "ĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢß",
+    "ŕáâăäĺćçčéęëěíîď",
+    "đńňóôőö÷řůúűüýţ˙",
+);
+
+const CP1251: &str = concat!(
+    "ЂЃ‚ѓ„…†‡€‰Љ‹ЊЌЋЏ",
+    "ђ‘’“”•–—\u{fffd}™љ›њќћџ",
+    "\u{a0}ЎўЈ¤Ґ¦§Ё©Є«¬\u{ad}®Ї",
+    "°±Ііґµ¶·ё№є»јЅѕї",
+    "АБВГДЕЖЗИЙКЛМНОП",
+    "РСТУФХЦЧШЩЪЫЬЭЮЯ",
+    "абвгдежзийклмноп",
+    "рстуфхцчшщъыьэюя",
+);
+
+const TIS620: &str = concat!(
+    "\u{80}\u{81}\u{82}\u{83}\u{84}\u{85}\u{86}\u{87}\u{88}\u{89}\u{8a}\u{8b}\u{8c}\u{8d}\u{8e}\u{8f}",
+    "\u{90}\u{91}\u{92}\u{93}\u{94}\u{95}\u{96}\u{97}\u{98}\u{99}\u{9a}\u{9b}\u{9c}\u{9d}\u{9e}\u{9f}",
+    "\u{fffd}กขฃคฅฆงจฉชซฌญฎฏ",
+    "ฐฑฒณดตถทธนบปผฝพฟ",
+    "ภมยรฤลฦวศษสหฬอฮฯ",
+    "ะ\u{e31}าำ\u{e34}\u{e35}\u{e36}\u{e37}\u{e38}\u{e39}\u{e3a}\u{fffd}\u{fffd}\u{fffd}\u{fffd}฿",
+    "เแโใไๅๆ\u{e47}\u{e48}\u{e49}\u{e4a}\u{e4b}\u{e4c}\u{e4d}\u{e4e}๏",
+    "๐๑๒๓๔๕๖๗๘๙๚๛\u{fffd}\u{fffd}\u{fffd}\u{fffd}",
+);
+
+#[cfg(test)]
+mod test {
+    use crate::hunspell::Hunspell;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+
+# comment
+PFX A Y 1
+PFX A   0     re         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+
+NEEDAFFIX X
+";
+
+    const DIC: &str = "5
+create/AD
+play/DS
+try/DS
+walk/D
+unwalkable/X
+";
+
+    #[test]
+    fn test_expand() {
+        let hunspell = Hunspell::from_bytes(DIC.as_bytes(), AFF.as_bytes()).unwrap();
+
+        assert_eq!(
+            hunspell.words(),
+            [
+                "create",
+                "created",
+                "play",
+                "played",
+                "plays",
+                "recreate",
+                "recreated",
+                "tried",
+                "tries",
+                "try",
+                "walk",
+                "walked",
+            ]
+        );
+
+        let trie = hunspell.to_trie();
+        assert!(trie.contains("recreated"));
+        assert!(!trie.contains("unwalkable"));
+    }
+
+    #[test]
+    fn test_flag_aliases_and_encoding() {
+        let aff = b"SET ISO8859-1\nFLAG long\nAF 1\nAF SaSb\nSFX Sa Y 1\nSFX Sa 0 s .\nSFX Sb Y 1\nSFX Sb 0 \xe9 .\n";
+        let dic = b"1\ncaf/1\n";
+        let hunspell = Hunspell::from_bytes(dic, aff).unwrap();
+
+        assert_eq!(hunspell.words(), ["caf", "cafs", "caf\u{e9}"]);
+
+        let aff = b"SET ISO8859-2\nAF 1 # aliases\nAF S # first\nSFX S Y 1\nSFX S 0 \xb1 .\n";
+        let dic = b"1\nkr\xf3l/1\n";
+        let hunspell = Hunspell::from_bytes(dic, aff).unwrap();
+        assert_eq!(hunspell.words(), ["kr\u{f3}l", "kr\u{f3}l\u{105}"]);
+
+        for (encoding, byte, expected) in [
+            ("ISO8859-5", 0xd0, '\u{430}'),
+            ("ISO8859-7", 0xe1, '\u{3b1}'),
+            ("KOI8-R", 0xc1, '\u{430}'),
+            ("microsoft-cp1250", 0x9a, '\u{161}'),
+            ("microsoft-cp1251", 0xe0, '\u{430}'),
+        ] {
+            let aff = format!("SET {encoding}\n");
+            let hunspell =
+                Hunspell::from_bytes(&[b'1', b'\n', byte, b'\n'], aff.as_bytes()).unwrap();
+            assert_eq!(hunspell.words(), [expected.to_string()]);
+        }
+        assert!(Hunspell::from_bytes(b"1\nx\n", b"SET EBCDIC\n").is_err());
+    }
+
+    #[test]
+    fn test_bom_and_escaped_slash() {
+        let aff = "\u{feff}SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n";
+        let dic = "\u{feff}3\ncafé/S\nand\\/or\nkm\\/h/S\n";
+        let hunspell = Hunspell::from_bytes(dic.as_bytes(), aff.as_bytes()).unwrap();
+
+        assert_eq!(
+            hunspell.words(),
+            ["and/or", "café", "cafés", "km/h", "km/hs"]
+        );
+    }
+}
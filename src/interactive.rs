@@ -0,0 +1,205 @@
+use std::io::{self, BufRead, Write};
+
+use colored::Colorize;
+
+use crate::{
+    filter::Filter,
+    spell_checker::{Corrector, SpellChecker, match_case},
+};
+
+pub const HELP: &str = "[number] replace, r) type replacement, i/Enter) ignore once, \
+                        a) ignore all, +) add to dictionary, x) save and skip rest, q) quit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Session {
+    Finished(String),
+    Quit,
+}
+
+pub fn run<F, R, W>(
+    spell_checker: &mut SpellChecker<F>,
+    name: &str,
+    text: &str,
+    filter: Filter,
+    mut input: R,
+    mut output: W,
+) -> io::Result<Session>
+where
+    F: Corrector,
+    R: BufRead,
+    W: Write,
+{
+    let mut corrected = String::with_capacity(text.len());
+    let mut copied_until = 0;
+
+    'misspellings: for misspelling in spell_checker.check_filtered(text, filter)? {
+        let word = &misspelling.word;
+        if spell_checker.contains(&word.to_lowercase()) || spell_checker.contains(word) {
+            continue;
+        }
+        let suggestions = misspelling.suggestions.clone();
+
+        let range = &misspelling.byte_range;
+        let line_start = text[..range.start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = text[range.end..]
+            .find('\n')
+            .map_or(text.len(), |idx| range.end + idx);
+        writeln!(
+            output,
+            "{}",
+            format!("{name}:{}:{}", misspelling.line, misspelling.column).bold()
+        )?;
+        writeln!(
+            output,
+            "    {}{}{}",
+            &text[line_start..range.start],
+            word.red().bold(),
+            &text[range.end..line_end]
+        )?;
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            writeln!(
+                output,
+                "  {}) {}",
+                i + 1,
+                match_case(word, suggestion).green()
+            )?;
+        }
+
+        let replacement = loop {
+            write!(output, "{HELP}\n> ")?;
+            output.flush()?;
+
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                break 'misspellings;
+            }
+            match answer.trim() {
+                "" | "i" => continue 'misspellings,
+                "a" => {
+                    spell_checker.dictionaries_mut().ignore(word);
+                    continue 'misspellings;
+                }
+                "+" => {
+                    let dictionaries = spell_checker.dictionaries_mut();
+                    if dictionaries.add_to_personal(word).is_err() {
+                        writeln!(output, "no personal dictionary, ignoring instead")?;
+                        dictionaries.ignore(word);
+                    }
+                    continue 'misspellings;
+                }
+                "r" => {
+                    write!(output, "replace with: ")?;
+                    output.flush()?;
+                    let mut custom = String::new();
+                    if input.read_line(&mut custom)? == 0 {
+                        return Ok(Session::Quit);
+                    }
+                    match custom.trim() {
+                        "" => writeln!(output, "empty replacement")?,
+                        custom => break custom.to_string(),
+                    }
+                }
+                "x" => break 'misspellings,
+                "q" => return Ok(Session::Quit),
+                answer => match answer.parse::<usize>() {
+                    Ok(n) if (1..=suggestions.len()).contains(&n) => {
+                        break match_case(word, &suggestions[n - 1]);
+                    }
+                    _ => writeln!(output, "unknown answer: {answer}")?,
+                },
+            }
+        };
+
+        corrected.push_str(&text[copied_until..range.start]);
+        corrected.push_str(&replacement);
+        copied_until = range.end;
+    }
+    corrected.push_str(&text[copied_until..]);
+
+    Ok(Session::Finished(corrected))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dictionary::DictionaryStack,
+        filter::Filter,
+        interactive::{Session, run},
+        spell_checker::{EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_interactive_session() {
+        colored::control::set_override(false);
+        let policy = EscalationPolicy::default();
+        let mut spell_checker = SpellChecker::new(
+            DictionaryStack::new(Trie::new(&mut [
+                "a", "donkey", "monkey", "on", "the", "roof",
+            ])),
+            |word, trie| policy.correct(word, trie),
+        );
+
+        let text = "Thw ronkey on the roof,\nqwxz the ronkey, qwxz zork.\nzork";
+        let answers = "1\n2\n7\na\n+\nr\nstork\nx\n";
+        let mut output = vec![];
+        let session = run(
+            &mut spell_checker,
+            "notes.txt",
+            text,
+            Filter::Plain,
+            answers.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        assert_eq!(
+            session,
+            Session::Finished("The monkey on the roof,\nqwxz the ronkey, qwxz stork.\nzork".into())
+        );
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("notes.txt:1:1\n    Thw ronkey on the roof,\n  1) The\n"));
+        assert!(output.contains("  1) donkey\n  2) monkey\n"));
+        assert!(output.contains("unknown answer: 7"));
+        assert!(output.contains("no personal dictionary, ignoring instead"));
+        assert!(spell_checker.dictionaries().is_ignored("qwxz"));
+        assert!(spell_checker.dictionaries().is_ignored("ronkey"));
+
+        let session = run(
+            &mut spell_checker,
+            "-",
+            "zork",
+            Filter::Plain,
+            "q\n".as_bytes(),
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(session, Session::Quit);
+
+        let mut output = vec![];
+        let answers = "r\n\nr\n  \nr\nfork\n".as_bytes();
+        let session = run(
+            &mut spell_checker,
+            "-",
+            "zork",
+            Filter::Plain,
+            answers,
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(session, Session::Finished("fork".into()));
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("empty replacement").count(), 2);
+
+        let session = run(
+            &mut spell_checker,
+            "-",
+            "zork",
+            Filter::Plain,
+            "r\n".as_bytes(),
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(session, Session::Quit);
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, BufRead, Write};
+
+use crate::spell_checker::{Corrector, SpellChecker};
+
+pub const BANNER: &str = concat!(
+    "@(#) International Ispell Version 3.1.20 (but really lev-automaton ",
+    env!("CARGO_PKG_VERSION"),
+    ")"
+);
+
+pub fn run<F, R, W>(spell_checker: &mut SpellChecker<F>, input: R, mut output: W) -> io::Result<()>
+where
+    F: Corrector,
+    R: BufRead,
+    W: Write,
+{
+    writeln!(output, "{BANNER}")?;
+    output.flush()?;
+
+    let mut terse = false;
+    for line in input.lines() {
+        let line = line?;
+        let mut chars = line.chars();
+
+        match chars.next() {
+            Some('*') => add_word(spell_checker, chars.as_str()),
+            Some('&') => add_word(spell_checker, &chars.as_str().to_lowercase()),
+            Some('@') => spell_checker.dictionaries_mut().ignore(chars.as_str()),
+            Some('#') | Some('~') | Some('+') | Some('-') => {}
+            Some('!') => terse = true,
+            Some('%') => terse = false,
+            Some('^') => check_line(spell_checker, chars.as_str(), terse, &mut output)?,
+            _ => check_line(spell_checker, &line, terse, &mut output)?,
+        }
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+fn add_word<F>(spell_checker: &mut SpellChecker<F>, word: &str)
+where
+    F: Corrector,
+{
+    let dictionaries = spell_checker.dictionaries_mut();
+    if dictionaries.add_to_personal(word).is_err() {
+        dictionaries.ignore(word);
+    }
+}
+
+fn check_line<F, W>(
+    spell_checker: &SpellChecker<F>,
+    line: &str,
+    terse: bool,
+    output: &mut W,
+) -> io::Result<()>
+where
+    F: Corrector,
+    W: Write,
+{
+    let misspellings = spell_checker.check_text(line);
+
+    for token in spell_checker.tokenizer(line) {
+        let misspelling = misspellings
+            .iter()
+            .find(|m| m.byte_range.contains(&token.byte_range.start));
+
+        match misspelling {
+            None if !terse => writeln!(output, "*")?,
+            None => {}
+            Some(m) if m.byte_range.start != token.byte_range.start => {}
+            Some(m) if m.suggestions.is_empty() => {
+                writeln!(output, "# {} {}", m.word, token.char_range.start)?
+            }
+            Some(m) => writeln!(
+                output,
+                "& {} {} {}: {}",
+                m.word,
+                m.suggestions.len(),
+                token.char_range.start,
+                m.suggestions.join(", ")
+            )?,
+        }
+    }
+
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ispell::{BANNER, run},
+        spell_checker::{EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_pipe_protocol() {
+        let policy = EscalationPolicy::default();
+        let mut spell_checker = SpellChecker::new(
+            Trie::new(&mut ["a", "donkey", "monkey", "on", "the", "roof"]),
+            |word, trie| policy.correct(word, trie),
+        );
+
+        let input = "^a donkep on the roof\nqwxz zork\n@zork\n!\nqwxz zork\n";
+        let mut output = vec![];
+        run(&mut spell_checker, input.as_bytes(), &mut output).unwrap();
+
+        let expected = [
+            BANNER,
+            "*",
+            "& donkep 1 2: donkey",
+            "*",
+            "*",
+            "*",
+            "",
+            "# qwxz 0",
+            "# zork 5",
+            "",
+            "# qwxz 0",
+            "",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
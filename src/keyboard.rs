@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use crate::levenshtein_automaton::EditCosts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Qwerty,
+    Qwertz,
+    Azerty,
+    Dvorak,
+}
+
+impl Layout {
+    pub fn rows(&self) -> [&'static str; 4] {
+        match self {
+            Layout::Qwerty => ["1234567890-=", "qwertyuiop[]", "asdfghjkl;'", "zxcvbnm,./"],
+            Layout::Qwertz => ["1234567890ß", "qwertzuiopü+", "asdfghjklöä#", "yxcvbnm,.-"],
+            Layout::Azerty => [
+                "&é\"'(-è_çà)=",
+                "azertyuiop^$",
+                "qsdfghjklmù*",
+                "wxcvbn,;:!",
+            ],
+            Layout::Dvorak => ["1234567890[]", "',.pyfgcrl/=", "aoeuidhtns-", ";qjkxbmwvz"],
+        }
+    }
+
+    fn position(&self, c: char) -> Option<(usize, usize)> {
+        self.rows().iter().enumerate().find_map(|(row, keys)| {
+            keys.chars()
+                .position(|key| key == c)
+                .map(|column| (row, column))
+        })
+    }
+
+    pub fn neighbors(&self, c: char) -> Vec<char> {
+        let Some((row, column)) = self.position(c) else {
+            return vec![];
+        };
+        let rows = self.rows();
+        let key = |row: usize, column: Option<usize>| {
+            column.and_then(|column| rows.get(row)?.chars().nth(column))
+        };
+
+        let mut neighbors = vec![
+            key(row, column.checked_sub(1)),
+            key(row, Some(column + 1)),
+            key(row + 1, column.checked_sub(1)),
+            key(row + 1, Some(column)),
+        ];
+        if row > 0 {
+            neighbors.extend([key(row - 1, Some(column)), key(row - 1, Some(column + 1))]);
+        }
+        neighbors.into_iter().flatten().collect()
+    }
+
+    pub fn is_adjacent(&self, a: char, b: char) -> bool {
+        self.neighbors(a).contains(&b)
+    }
+
+    pub fn edit_costs(&self) -> EditCosts {
+        let substitutions: BTreeMap<(char, char), usize> = self
+            .rows()
+            .iter()
+            .flat_map(|keys| keys.chars())
+            .flat_map(|key| {
+                self.neighbors(key)
+                    .into_iter()
+                    .map(move |neighbor| ((key, neighbor), 1))
+            })
+            .collect();
+
+        EditCosts {
+            edit: 2,
+            substitutions,
+            doubled_key: 1,
+            missed_double: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        automaton::Deterministic,
+        keyboard::Layout,
+        levenshtein_automaton::LevenshteinAutomaton,
+        spell_checker::{EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_layouts() {
+        let mut neighbors = Layout::Qwerty.neighbors('s');
+        neighbors.sort();
+        assert_eq!(neighbors, vec!['a', 'd', 'e', 'w', 'x', 'z']);
+        assert!(Layout::Qwerty.is_adjacent('q', '1'));
+        assert!(Layout::Qwertz.is_adjacent('z', 'u'));
+        assert!(!Layout::Qwerty.is_adjacent('z', 'u'));
+        assert!(Layout::Azerty.is_adjacent('a', 'z'));
+        assert!(Layout::Dvorak.is_adjacent('a', 'o'));
+        assert!(Layout::Qwerty.neighbors('€').is_empty());
+
+        let costs = Layout::Qwerty.edit_costs();
+        assert_eq!(costs.distance("hpt", "hot"), 1);
+        assert_eq!(costs.distance("hpt", "hat"), 2);
+        assert_eq!(costs.distance("heello", "hello"), 1);
+        assert_eq!(costs.distance("adress", "address"), 1);
+        assert_eq!(costs.distance("adress", "dress"), 2);
+        assert_eq!(costs.distance("", "ab"), 4);
+    }
+
+    #[test]
+    fn test_keyboard_suggestions() {
+        let costs = Layout::Qwerty.edit_costs();
+        let trie = Trie::new(&mut ["hat", "hit", "hot", "hut", "hello", "address", "dress"]);
+
+        let aut: LevenshteinAutomaton<Deterministic> =
+            LevenshteinAutomaton::with_costs("hpy", 2, &costs).into();
+        assert_eq!(trie.filter(&aut), vec!["hot"]);
+
+        let plain = EscalationPolicy::default();
+        let keyboard = EscalationPolicy {
+            costs,
+            ..EscalationPolicy::default()
+        };
+        assert_eq!(
+            plain.correct("hpt", &trie),
+            vec!["hat", "hit", "hot", "hut"]
+        );
+        assert_eq!(
+            keyboard.correct("hpt", &trie),
+            vec!["hot", "hat", "hit", "hut"]
+        );
+
+        let spell_checker = SpellChecker::new(trie, |word, trie| keyboard.correct(word, trie));
+        assert_eq!(
+            spell_checker.check_word("heello"),
+            Err(vec!["hello".into()])
+        );
+        assert_eq!(
+            spell_checker.check_word("adress"),
+            Err(vec!["address".into(), "dress".into()])
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use crate::automaton::{Automaton, Deterministic, NonDeterministic, StateId, Transition};
+use std::collections::BTreeMap;
+
+use crate::{
+    automaton::{Automaton, Deterministic, NonDeterministic, StateId, Transition},
+    matcher::Matcher,
+};
 
 #[derive(Debug)]
 pub struct LevenshteinAutomaton<T>(Automaton<T>);
@@ -9,40 +14,136 @@ impl<T> LevenshteinAutomaton<T> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditCosts {
+    pub edit: usize,
+    pub substitutions: BTreeMap<(char, char), usize>,
+    pub doubled_key: usize,
+    pub missed_double: usize,
+}
+
+impl Default for EditCosts {
+    fn default() -> Self {
+        Self {
+            edit: 1,
+            substitutions: BTreeMap::new(),
+            doubled_key: 1,
+            missed_double: 1,
+        }
+    }
+}
+
+impl EditCosts {
+    pub fn substitution(&self, typed: char, intended: char) -> usize {
+        match typed == intended {
+            true => 0,
+            false => self
+                .substitutions
+                .get(&(typed, intended))
+                .copied()
+                .unwrap_or(self.edit)
+                .min(self.edit),
+        }
+    }
+
+    fn cheap_substitutions(&self, typed: char) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.substitutions
+            .range((typed, char::MIN)..=(typed, char::MAX))
+            .map(|((_, intended), cost)| (*intended, *cost))
+            .filter(|(_, cost)| *cost < self.edit)
+    }
+
+    pub fn distance(&self, typed: &str, intended: &str) -> usize {
+        let typed: Vec<char> = typed.chars().collect();
+        let intended: Vec<char> = intended.chars().collect();
+
+        let extra = |i: usize| match i > 1 && typed[i - 1] == typed[i - 2] {
+            true => self.doubled_key.min(self.edit),
+            false => self.edit,
+        };
+        let missed = |i: usize, c: char| match i > 0 && typed[i - 1] == c {
+            true => self.missed_double.min(self.edit),
+            false => self.edit,
+        };
+
+        let mut row: Vec<usize> = vec![0; intended.len() + 1];
+        for j in 1..=intended.len() {
+            row[j] = row[j - 1] + missed(0, intended[j - 1]);
+        }
+        for i in 1..=typed.len() {
+            let mut diagonal = row[0];
+            row[0] += extra(i);
+            for j in 1..=intended.len() {
+                let substitution = diagonal + self.substitution(typed[i - 1], intended[j - 1]);
+                diagonal = row[j];
+                row[j] = substitution
+                    .min(row[j] + extra(i))
+                    .min(row[j - 1] + missed(i, intended[j - 1]));
+            }
+        }
+
+        row[intended.len()]
+    }
+}
+
 impl LevenshteinAutomaton<NonDeterministic> {
     #[must_use]
     pub fn new(word: &str, k: usize) -> Self {
+        Self::with_costs(word, k, &EditCosts::default())
+    }
+
+    #[must_use]
+    pub fn with_costs(word: &str, max_cost: usize, costs: &EditCosts) -> Self {
         let mut automaton: Automaton<NonDeterministic> = Automaton::default();
 
-        let word_len = word.chars().count();
+        let chars: Vec<char> = word.chars().collect();
+        let word_len = chars.len();
         let states: Vec<Vec<StateId>> = (0..=word_len)
-            .map(|_| (0..=k).map(|_| automaton.add_state()).collect())
+            .map(|_| (0..=max_cost).map(|_| automaton.add_state()).collect())
             .collect();
 
-        for (i, c) in word.chars().enumerate() {
-            for e in 0..=k {
-                automaton.add_transition(states[i][e], states[i + 1][e], Transition::Is(c));
-                if e < k {
-                    automaton.add_transition(states[i][e], states[i][e + 1], Transition::Star);
-                    automaton.add_transition(
-                        states[i][e],
-                        states[i + 1][e + 1],
-                        Transition::Epsilon,
-                    );
-                    automaton.add_transition(states[i][e], states[i + 1][e + 1], Transition::Star);
+        for i in 0..=word_len {
+            for e in 0..=max_cost {
+                let from = states[i][e];
+                let next_char = chars.get(i).copied();
+                if let Some(c) = next_char {
+                    automaton.add_transition(from, states[i + 1][e], Transition::Is(c));
+                }
+                if e + costs.edit <= max_cost {
+                    let e = e + costs.edit;
+                    automaton.add_transition(from, states[i][e], Transition::Star);
+                    if next_char.is_some() {
+                        automaton.add_transition(from, states[i + 1][e], Transition::Epsilon);
+                        automaton.add_transition(from, states[i + 1][e], Transition::Star);
+                    }
+                }
+
+                if let Some(c) = next_char {
+                    for (intended, cost) in costs.cheap_substitutions(c) {
+                        if e + cost <= max_cost {
+                            let to = states[i + 1][e + cost];
+                            automaton.add_transition(from, to, Transition::Is(intended));
+                        }
+                    }
+                }
+                if i > 0 && costs.missed_double < costs.edit && e + costs.missed_double <= max_cost
+                {
+                    let to = states[i][e + costs.missed_double];
+                    automaton.add_transition(from, to, Transition::Is(chars[i - 1]));
+                }
+                if i > 0
+                    && next_char == Some(chars[i - 1])
+                    && costs.doubled_key < costs.edit
+                    && e + costs.doubled_key <= max_cost
+                {
+                    let to = states[i + 1][e + costs.doubled_key];
+                    automaton.add_transition(from, to, Transition::Epsilon);
                 }
             }
         }
 
-        for e in 0..=k {
-            if e < k {
-                automaton.add_transition(
-                    states[word_len][e],
-                    states[word_len][e + 1],
-                    Transition::Star,
-                );
-            }
-            automaton.make_state_final(states[word_len][e]);
+        for state in &states[word_len] {
+            automaton.make_state_final(*state);
         }
 
         Self(automaton)
@@ -55,9 +156,52 @@ impl From<LevenshteinAutomaton<NonDeterministic>> for LevenshteinAutomaton<Deter
     }
 }
 
+impl<T> Matcher for LevenshteinAutomaton<T>
+where
+    Automaton<T>: Matcher,
+{
+    type State = <Automaton<T> as Matcher>::State;
+
+    fn start(&self) -> Self::State {
+        self.0.start()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        self.0.accept(state, c)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.0.is_match(state)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        self.0.can_match(state)
+    }
+}
+
+pub fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{automaton::Deterministic, levenshtein_automaton::LevenshteinAutomaton};
+    use crate::{
+        automaton::Deterministic,
+        levenshtein_automaton::{LevenshteinAutomaton, distance},
+    };
 
     const FOOD: &str = "food";
 
@@ -219,4 +363,22 @@ mod test {
             assert!(!lev_aut.0.recognizes(word))
         }
     }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance(FOOD, FOOD), 0);
+        assert_eq!(distance("", FOOD), 4);
+
+        for word in FOOD_LEV_1 {
+            assert_eq!(distance(FOOD, word), 1)
+        }
+
+        for word in FOOD_LEV_2 {
+            assert_eq!(distance(FOOD, word), 2)
+        }
+
+        for word in FOOD_LEV_3 {
+            assert_eq!(distance(word, FOOD), 3)
+        }
+    }
 }
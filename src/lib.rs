@@ -1,4 +1,24 @@
 pub mod automaton;
+pub mod cache;
+pub mod compound;
+pub mod daemon;
+pub mod dictionary;
+pub mod diff;
+pub mod error_model;
+pub mod filter;
+pub mod hunspell;
+pub mod interactive;
+pub mod ispell;
+pub mod keyboard;
 pub mod levenshtein_automaton;
+pub mod lsp;
+pub mod matcher;
+pub mod pattern;
+pub mod phonetic;
+pub mod reload;
+pub mod report;
+pub mod segmentation;
 pub mod spell_checker;
+pub mod tokenizer;
+pub mod transducer;
 pub mod trie;
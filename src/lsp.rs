@@ -0,0 +1,528 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, ErrorKind, Write},
+    sync::Arc,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    dictionary::PersonalDictionary,
+    reload::Reloadable,
+    spell_checker::{Corrector, Misspelling, SpellChecker},
+};
+
+pub const ADD_TO_DICTIONARY: &str = "lev-automaton.addToDictionary";
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32803;
+
+struct Document {
+    text: String,
+    misspellings: Vec<Misspelling>,
+}
+
+pub struct Server<F>
+where
+    F: Corrector,
+{
+    handle: Arc<Reloadable<SpellChecker<F>>>,
+    generation: usize,
+    documents: HashMap<String, Document>,
+}
+
+impl<F> Server<F>
+where
+    F: Corrector,
+{
+    pub fn new(handle: Arc<Reloadable<SpellChecker<F>>>) -> Self {
+        Self {
+            generation: handle.generation(),
+            handle,
+            documents: HashMap::new(),
+        }
+    }
+
+    pub fn run<R, W>(&mut self, mut input: R, mut output: W) -> io::Result<()>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        while let Some(message) = read_message(&mut input)? {
+            if message["method"] == "exit" {
+                break;
+            }
+            for response in self.handle(&message) {
+                write_message(&mut output, &response)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let mut messages = match self.handle.generation() == self.generation {
+            true => vec![],
+            false => self.recheck_documents(),
+        };
+        messages.extend(self.dispatch(message));
+        messages
+    }
+
+    fn dispatch(&mut self, message: &Value) -> Vec<Value> {
+        let params = &message["params"];
+        let id = &message["id"];
+
+        let result = match message["method"].as_str().unwrap_or_default() {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "codeActionProvider": true,
+                    "executeCommandProvider": { "commands": [ADD_TO_DICTIONARY] },
+                },
+                "serverInfo": {
+                    "name": "lev-automaton",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                return self.update_document(&document["uri"], &document["text"]);
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let text = changes.and_then(|changes| changes.last());
+                return match text {
+                    Some(change) => {
+                        self.update_document(&params["textDocument"]["uri"], &change["text"])
+                    }
+                    None => vec![],
+                };
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, vec![])];
+            }
+            "textDocument/codeAction" => self.code_actions(params),
+            "workspace/executeCommand" => return self.execute_command(id, params),
+            _ => Err((METHOD_NOT_FOUND, "method not found".to_string())),
+        };
+
+        if id.is_null() {
+            return vec![];
+        }
+        match result {
+            Ok(result) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            Err((code, message)) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            })],
+        }
+    }
+
+    fn update_document(&mut self, uri: &Value, text: &Value) -> Vec<Value> {
+        let (Some(uri), Some(text)) = (uri.as_str(), text.as_str()) else {
+            return vec![];
+        };
+
+        let document = Document {
+            misspellings: self.handle.snapshot().check_text(text),
+            text: text.to_string(),
+        };
+        let notification = publish_diagnostics(uri, diagnostics(&document));
+        self.documents.insert(uri.to_string(), document);
+        vec![notification]
+    }
+
+    fn code_actions(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(json!([]));
+        };
+        let (Some(start), Some(end)) = (
+            position_offset(&document.text, &params["range"]["start"]),
+            position_offset(&document.text, &params["range"]["end"]),
+        ) else {
+            return Err((INVALID_PARAMS, "invalid range".to_string()));
+        };
+
+        let mut actions = vec![];
+        for misspelling in &document.misspellings {
+            let range = &misspelling.byte_range;
+            if range.start > end || range.end < start {
+                continue;
+            }
+
+            let lsp_range = lsp_range(&document.text, range.start, range.end);
+            for suggestion in &misspelling.suggestions {
+                actions.push(json!({
+                    "title": format!("Replace with '{suggestion}'"),
+                    "kind": "quickfix",
+                    "edit": {
+                        "changes": { uri: [{ "range": lsp_range, "newText": suggestion }] },
+                    },
+                }));
+            }
+            actions.push(json!({
+                "title": format!("Add '{}' to dictionary", misspelling.word),
+                "kind": "quickfix",
+                "command": {
+                    "title": "Add to dictionary",
+                    "command": ADD_TO_DICTIONARY,
+                    "arguments": [misspelling.word],
+                },
+            }));
+        }
+
+        Ok(Value::Array(actions))
+    }
+
+    fn execute_command(&mut self, id: &Value, params: &Value) -> Vec<Value> {
+        let word = params["arguments"][0].as_str();
+        let (true, Some(word)) = (params["command"] == ADD_TO_DICTIONARY, word) else {
+            return vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": INVALID_PARAMS, "message": "unknown command" },
+            })];
+        };
+
+        if let Err(e) = self.add_to_dictionary(word) {
+            return vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": REQUEST_FAILED, "message": e.to_string() },
+            })];
+        }
+
+        let mut messages = vec![json!({ "jsonrpc": "2.0", "id": id, "result": null })];
+        messages.extend(self.recheck_documents());
+        messages
+    }
+
+    fn recheck_documents(&mut self) -> Vec<Value> {
+        self.generation = self.handle.generation();
+        let spell_checker = self.handle.snapshot();
+        self.documents
+            .iter_mut()
+            .map(|(uri, document)| {
+                document.misspellings = spell_checker.check_text(&document.text);
+                publish_diagnostics(uri, diagnostics(document))
+            })
+            .collect()
+    }
+
+    fn add_to_dictionary(&self, word: &str) -> io::Result<()> {
+        let personal = self
+            .handle
+            .snapshot()
+            .dictionaries()
+            .personal()
+            .map(|p| p.path().to_path_buf());
+        let Some(personal) = personal else {
+            return Err(io::Error::new(
+                ErrorKind::NotFound,
+                "no personal dictionary configured; set LEV_SPELL_CHECK_PERSONAL_DICT",
+            ));
+        };
+        let added = self
+            .handle
+            .update(|spell_checker| spell_checker.dictionaries_mut().add_to_personal(word));
+        match added {
+            Some(added) => added.map(drop),
+            None => {
+                PersonalDictionary::append(&personal, word)?;
+                self.handle.reload()
+            }
+        }
+    }
+}
+
+fn diagnostics(document: &Document) -> Vec<Value> {
+    document
+        .misspellings
+        .iter()
+        .map(|misspelling| {
+            let range = &misspelling.byte_range;
+            let mut message = format!("Unknown word: {}", misspelling.word);
+            if !misspelling.suggestions.is_empty() {
+                message.push_str(&format!(
+                    " (did you mean {}?)",
+                    misspelling.suggestions.join(", ")
+                ));
+            }
+            json!({
+                "range": lsp_range(&document.text, range.start, range.end),
+                "severity": 3,
+                "source": "lev-automaton",
+                "message": message,
+            })
+        })
+        .collect()
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn lsp_range(text: &str, start: usize, end: usize) -> Value {
+    json!({ "start": lsp_position(text, start), "end": lsp_position(text, end) })
+}
+
+fn lsp_position(text: &str, byte_offset: usize) -> Value {
+    let line_start = text[..byte_offset].rfind('\n').map_or(0, |idx| idx + 1);
+    json!({
+        "line": text[..line_start].matches('\n').count(),
+        "character": text[line_start..byte_offset].encode_utf16().count(),
+    })
+}
+
+fn position_offset(text: &str, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let line_start = match line {
+        0 => 0,
+        _ => text.match_indices('\n').nth(line - 1)?.0 + 1,
+    };
+    let line_text = text[line_start..].split('\n').next().unwrap_or_default();
+
+    let mut units = 0;
+    for (idx, c) in line_text.char_indices() {
+        if units >= character {
+            return Some(line_start + idx);
+        }
+        units += c.len_utf16();
+    }
+    Some(line_start + line_text.len())
+}
+
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; content_length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, io::BufReader, process, sync::Arc};
+
+    use serde_json::{Value, json};
+
+    use crate::{
+        dictionary::{DictionaryStack, PersonalDictionary},
+        lsp::{ADD_TO_DICTIONARY, Server, read_message, write_message},
+        reload::Reloadable,
+        spell_checker::{Corrector, EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    fn run_session<F: Corrector>(server: &mut Server<F>, messages: &[Value]) -> Vec<Value> {
+        let mut input = vec![];
+        for message in messages {
+            write_message(&mut input, message).unwrap();
+        }
+
+        let mut output = vec![];
+        server.run(input.as_slice(), &mut output).unwrap();
+
+        let mut reader = BufReader::new(output.as_slice());
+        let mut messages = vec![];
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn test_scripted_session() {
+        let path = env::temp_dir().join(format!("lev-automaton-lsp-{}.txt", process::id()));
+        fs::write(&path, "rustacean").unwrap();
+        let personal = path.clone();
+        let handle = Reloadable::new(move || {
+            let dictionaries = DictionaryStack::new(Trie::new(&mut [
+                "a", "donkey", "monkey", "on", "the", "roof",
+            ]))
+            .with_personal(PersonalDictionary::load(&personal)?);
+            Ok(SpellChecker::from_corrector(
+                dictionaries,
+                EscalationPolicy::default(),
+            ))
+        })
+        .unwrap();
+        let uri = "file:///notes.txt";
+
+        let messages = run_session(
+            &mut Server::new(Arc::new(handle)),
+            &[
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+                json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+                json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                    "textDocument": { "uri": uri, "languageId": "plaintext", "version": 1,
+                        "text": "the roof\nÁ donkep on the roof" },
+                }}),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/codeAction", "params": {
+                    "textDocument": { "uri": uri },
+                    "range": { "start": { "line": 1, "character": 4 }, "end": { "line": 1, "character": 4 } },
+                    "context": { "diagnostics": [] },
+                }}),
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "workspace/executeCommand", "params": {
+                    "command": ADD_TO_DICTIONARY, "arguments": ["Á"],
+                }}),
+                json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+                json!({ "jsonrpc": "2.0", "method": "exit" }),
+            ],
+        );
+
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[0]["id"], 1);
+        assert_eq!(
+            messages[0]["result"]["capabilities"]["codeActionProvider"],
+            true
+        );
+
+        let diagnostics = &messages[1]["params"]["diagnostics"];
+        assert_eq!(diagnostics.as_array().unwrap().len(), 2);
+        assert_eq!(
+            diagnostics[1]["range"],
+            json!({ "start": { "line": 1, "character": 2 }, "end": { "line": 1, "character": 8 } })
+        );
+
+        let actions = messages[2]["result"].as_array().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0]["edit"]["changes"][uri][0]["newText"], "donkey");
+        assert_eq!(actions[1]["command"]["arguments"], json!(["donkep"]));
+
+        assert_eq!(messages[3]["id"], 3);
+        assert!(messages[3]["error"].is_null());
+        let diagnostics = &messages[4]["params"]["diagnostics"];
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "rustacean\nÁ\n");
+
+        assert_eq!(messages[5]["id"], 4);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_republish_after_reload() {
+        let path = env::temp_dir().join(format!("lev-automaton-lsp-main-{}.txt", process::id()));
+        fs::write(
+            &path,
+            "the
+roof
+",
+        )
+        .unwrap();
+        let main = path.clone();
+        let handle = Arc::new(
+            Reloadable::new(move || {
+                Ok(SpellChecker::from_corrector(
+                    DictionaryStack::load(std::slice::from_ref(&main), None)?,
+                    EscalationPolicy::default(),
+                ))
+            })
+            .unwrap(),
+        );
+        let mut server = Server::new(Arc::clone(&handle));
+
+        let messages = server.handle(&json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///a.txt", "text": "the rooof" } } }));
+        assert_eq!(
+            messages[0]["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        fs::write(
+            &path,
+            "the
+roof
+rooof
+",
+        )
+        .unwrap();
+        handle.reload().unwrap();
+        let messages = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" }));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["params"]["uri"], "file:///a.txt");
+        assert_eq!(messages[0]["params"]["diagnostics"], json!([]));
+        assert_eq!(messages[1]["id"], 1);
+
+        assert_eq!(
+            server
+                .handle(&json!({ "jsonrpc": "2.0", "method": "initialized" }))
+                .len(),
+            0
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_add_without_personal_dictionary() {
+        let handle = Reloadable::new(|| {
+            Ok(SpellChecker::from_corrector(
+                Trie::new(&mut ["the", "roof"]),
+                EscalationPolicy::default(),
+            ))
+        })
+        .unwrap();
+
+        let messages = run_session(
+            &mut Server::new(Arc::new(handle)),
+            &[
+                json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                    "textDocument": { "uri": "file:///a.txt", "text": "the rooof" },
+                }}),
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "workspace/executeCommand", "params": {
+                    "command": ADD_TO_DICTIONARY, "arguments": ["rooof"],
+                }}),
+            ],
+        );
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["id"], 1);
+        assert!(
+            messages[1]["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("no personal dictionary")
+        );
+    }
+}
@@ -1,58 +1,872 @@
 use std::{
-    env,
-    io::{self, BufRead},
-    path::Path,
+    env, fs,
+    io::{self, IsTerminal, Read},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+    time::Duration,
 };
 
+#[cfg(unix)]
+use std::os::unix::{
+    fs::FileTypeExt,
+    net::{UnixListener, UnixStream},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
 use lev_automaton::{
-    automaton::Deterministic, levenshtein_automaton::LevenshteinAutomaton,
-    spell_checker::SpellChecker, trie::Trie,
+    automaton::{Deterministic, NonDeterministic},
+    compound::CompoundConfig,
+    daemon,
+    dictionary::DictionaryStack,
+    diff::unified_diff,
+    error_model::{ChannelCorrector, ErrorModel, NoisyChannel, parse_frequencies},
+    filter::{Filter, Syntax},
+    interactive::{self, Session},
+    ispell,
+    keyboard::Layout,
+    levenshtein_automaton::LevenshteinAutomaton,
+    matcher::{Matcher, Prefix},
+    pattern::PatternAutomaton,
+    reload::Reloadable,
+    report::{FileReport, Format, write_report},
+    segmentation::Segmenter,
+    spell_checker::{Corrector, EscalationPolicy, Misspelling, SpellChecker, rank_suggestions},
+    transducer::{RuleCorrector, Transducer},
+    trie::Trie,
 };
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone, Parser)]
+#[command(version, about = "Spell checker backed by Levenshtein automata")]
+struct Cli {
+    /// Dictionary file (plain word list, Hunspell .dic or binary index); may be repeated
+    #[arg(short, long = "dict", global = true)]
+    dicts: Vec<PathBuf>,
+
+    /// Personal word list, appended to when words are added
+    #[arg(long, global = true)]
+    personal: Option<PathBuf>,
+
+    /// Fixed edit distance; adaptive escalation is used when omitted
+    #[arg(short = 'k', long, global = true)]
+    degree: Option<usize>,
+
+    /// Maximum number of suggestions shown per word
+    #[arg(short = 'n', long, global = true, default_value_t = 10)]
+    max_suggestions: usize,
+
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Extract prose from markup or source code before checking; auto picks by file extension
+    #[arg(long, global = true, value_enum, default_value_t = FilterChoice::Auto)]
+    filter: FilterChoice,
+
+    /// Output format of the check command
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Accept compounds of dictionary words
+    #[arg(long, global = true)]
+    compounds: bool,
+
+    /// Linking morpheme allowed between compound parts; may be repeated
+    #[arg(long = "linker", global = true)]
+    linkers: Vec<String>,
+
+    /// Split camelCase, PascalCase, snake_case, kebab-case and digit boundaries into words
+    #[arg(long, global = true)]
+    identifiers: bool,
+
+    /// Also suggest words reachable through English rewrite rules (ph/f, colour/color, ie/ei, ...)
+    #[arg(long, global = true)]
+    rules: bool,
+
+    /// Also suggest words that sound alike (Double Metaphone)
+    #[arg(long, global = true)]
+    phonetic: bool,
+
+    /// Rank adjacent-key, doubled-key and missed-key typos above other edits
+    #[arg(long, global = true, value_enum)]
+    keyboard: Option<KeyboardChoice>,
+
+    /// Rank suggestions with a noisy channel trained on a JSON list of [misspelling, correct] pairs
+    #[arg(long, global = true)]
+    error_model: Option<PathBuf>,
+
+    /// Word frequency list ("word count" per line) used as the noisy channel prior
+    #[arg(long, global = true, requires = "error_model")]
+    word_frequencies: Option<PathBuf>,
+
+    /// Number of misspelled words whose suggestions are cached; 0 disables the cache
+    #[arg(long, global = true, default_value_t = 4096)]
+    cache_size: usize,
+
+    /// Speak the ispell pipe protocol on stdin/stdout
+    #[arg(short = 'a')]
+    pipe: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Check files, globs or stdin ("-") for misspellings
+    Check { inputs: Vec<String> },
+    /// Replace misspellings that have a single or confident suggestion
+    Correct {
+        inputs: Vec<String>,
+        /// Rewrite files in place, keeping a backup of the original
+        #[arg(short, long, conflicts_with = "diff")]
+        in_place: bool,
+        /// Suffix of backup files written by --in-place; empty disables backups
+        #[arg(long, default_value = ".bak")]
+        backup_suffix: String,
+        /// Print a unified diff instead of the corrected text
+        #[arg(long)]
+        diff: bool,
+        /// Also apply the top suggestion when its confidence reaches this value (0 to 1)
+        #[arg(long)]
+        min_confidence: Option<f64>,
+    },
+    /// Step through misspellings in files and fix them interactively
+    Interactive { inputs: Vec<String> },
+    /// Print suggestions for a single word
+    Suggest { word: String },
+    /// Compile the main dictionary into a binary index
+    BuildIndex { output: PathBuf },
+    /// List dictionary words matching a pattern ("?" and "*" wildcards)
+    Query {
+        pattern: String,
+        /// Treat the pattern as a word and list words within this edit distance
+        #[arg(short, long)]
+        fuzzy: Option<usize>,
+        /// Only list words starting with this prefix
+        #[arg(short, long, default_value = "")]
+        prefix: String,
+    },
+    /// Print the Levenshtein automaton of a word in Graphviz DOT format
+    Dot {
+        word: String,
+        /// Print the automaton before subset construction
+        #[arg(long)]
+        nfa: bool,
+    },
+    /// Keep the dictionaries loaded and answer JSON requests from clients
+    Serve {
+        #[command(flatten)]
+        endpoint: Endpoint,
+        /// Reload the dictionaries when their files change, checking every SECONDS
+        #[arg(long, value_name = "SECONDS")]
+        watch: Option<u64>,
+    },
+    /// Send a request to a running server
+    Client {
+        #[command(flatten)]
+        endpoint: Endpoint,
+        #[command(subcommand)]
+        request: ClientRequest,
+    },
+}
+
+#[derive(Debug, Clone, clap::Args)]
+struct Endpoint {
+    /// Unix domain socket path; defaults to lev-automaton.sock in the temp directory
+    #[arg(long, conflicts_with = "port")]
+    socket: Option<PathBuf>,
+    /// Use HTTP on this localhost port instead of a Unix socket
+    #[arg(long)]
+    port: Option<u16>,
+}
+
+impl Endpoint {
+    #[cfg(unix)]
+    fn socket_path(&self) -> PathBuf {
+        self.socket
+            .clone()
+            .unwrap_or_else(|| env::temp_dir().join("lev-automaton.sock"))
+    }
+
+    fn request(&self, request: &Value) -> io::Result<Value> {
+        let response = match self.port {
+            Some(port) => daemon::request_http(("127.0.0.1", port), request)?,
+            #[cfg(unix)]
+            None => daemon::request_unix(&self.socket_path(), request)?,
+            #[cfg(not(unix))]
+            None => return Err(unix_sockets_unsupported()),
+        };
+        match response["error"].as_str() {
+            Some(error) => Err(io::Error::other(error.to_string())),
+            None => Ok(response),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum ClientRequest {
+    /// Check files, globs or stdin ("-") for misspellings
+    Check { inputs: Vec<String> },
+    /// Print suggestions for a single word
+    Suggest { word: String },
+    /// List dictionary words within an edit distance of a word
+    FuzzyQuery {
+        word: String,
+        #[arg(default_value_t = 1)]
+        distance: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FilterChoice {
+    Auto,
+    Plain,
+    Markdown,
+    Html,
+    Source,
+}
 
-fn main() {
-    let path = env::var("LEV_SPELL_CHECK_DICT_PATH").unwrap_or("/usr/share/dict/words".into());
-    let trie = Trie::load_from_file(Path::new(&path))
-        .unwrap_or_else(|_| panic!("Unable to open dictionary file: {path}"));
-    let spell_checker = SpellChecker::new(trie, |word, trie| {
-        let aut = LevenshteinAutomaton::new(word, 1);
-        let aut: LevenshteinAutomaton<Deterministic> = aut.into();
-        trie.filter(aut.get_automaton())
-    });
-
-    let stdin = io::stdin();
-    let reader = stdin.lock();
-
-    for line in reader.lines() {
-        let line = line.expect("STDIN FAIL");
-
-        _ = line
-            .split_whitespace()
-            .map(|word| match spell_checker.check_word(word) {
-                Ok(()) => {
-                    print!("{word} ")
-                }
-                Err(possible_corrections) => {
-                    print!("{}", word.red().strikethrough());
-                    match possible_corrections.len() {
-                        0 => {}
-                        1 => {
-                            print!(" -> {} ", possible_corrections[0].green().italic())
-                        }
-                        _ => {
-                            print!(" -> {{ ");
-                            for (i, correction) in possible_corrections.iter().enumerate() {
-                                print!("{}", correction.green());
-                                if i != possible_corrections.len() - 1 {
-                                    print!(", ")
-                                }
-                            }
-                            print!(" }} ");
-                        }
+impl FilterChoice {
+    fn select(self, source: Option<&Path>) -> Filter {
+        let detected = source.map(Filter::for_path).unwrap_or_default();
+        match self {
+            FilterChoice::Auto => detected,
+            FilterChoice::Plain => Filter::Plain,
+            FilterChoice::Markdown => Filter::Markdown,
+            FilterChoice::Html => Filter::Html,
+            FilterChoice::Source => match detected {
+                Filter::Source(syntax) => Filter::Source(syntax),
+                _ => Filter::Source(Syntax::CLike),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum KeyboardChoice {
+    Qwerty,
+    Qwertz,
+    Azerty,
+    Dvorak,
+}
+
+impl From<KeyboardChoice> for Layout {
+    fn from(choice: KeyboardChoice) -> Self {
+        match choice {
+            KeyboardChoice::Qwerty => Layout::Qwerty,
+            KeyboardChoice::Qwertz => Layout::Qwertz,
+            KeyboardChoice::Azerty => Layout::Azerty,
+            KeyboardChoice::Dvorak => Layout::Dvorak,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Jsonl,
+    Sarif,
+    Checkstyle,
+    Tsv,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("lev-automaton: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(cli: Cli) -> io::Result<ExitCode> {
+    match cli.color {
+        ColorChoice::Auto => colored::control::set_override(io::stdout().is_terminal()),
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+    }
+
+    if let Some(Command::Dot { word, nfa }) = &cli.command {
+        let aut = LevenshteinAutomaton::new(word, cli.degree.unwrap_or(1));
+        if *nfa {
+            print!("{}", aut.get_automaton().to_dot());
+        } else {
+            let aut: LevenshteinAutomaton<Deterministic> = aut.into();
+            print!("{}", aut.get_automaton().to_dot());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(Command::Client { endpoint, request }) = &cli.command {
+        return client(endpoint, request, cli.max_suggestions);
+    }
+
+    if let Some(Command::Serve { endpoint, watch }) = &cli.command {
+        return serve(&cli, endpoint, *watch);
+    }
+
+    let (paths, personal) = dictionary_paths(&cli);
+    let mut spell_checker = build_spell_checker(&cli, load_dictionaries(&paths, &personal)?)?;
+
+    if cli.pipe {
+        ispell::run(&mut spell_checker, io::stdin().lock(), io::stdout().lock())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    match cli.command {
+        None => check(
+            &spell_checker,
+            &[],
+            cli.filter,
+            cli.format,
+            cli.max_suggestions,
+        ),
+        Some(Command::Check { inputs }) => check(
+            &spell_checker,
+            &inputs,
+            cli.filter,
+            cli.format,
+            cli.max_suggestions,
+        ),
+        Some(Command::Correct {
+            inputs,
+            in_place,
+            backup_suffix,
+            diff,
+            min_confidence,
+        }) => {
+            let mode = match (in_place, diff) {
+                (true, _) => CorrectMode::InPlace { backup_suffix },
+                (_, true) => CorrectMode::Diff,
+                _ => CorrectMode::Stdout,
+            };
+            correct(&spell_checker, &inputs, cli.filter, &mode, min_confidence)
+        }
+        Some(Command::Interactive { inputs }) => {
+            for source in expand_inputs(&inputs)? {
+                let Some(path) = source else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "interactive mode reads answers from stdin and needs file arguments",
+                    ));
+                };
+                let text = fs::read_to_string(&path)?;
+                let name = path.display().to_string();
+                match interactive::run(
+                    &mut spell_checker,
+                    &name,
+                    &text,
+                    cli.filter.select(Some(&path)),
+                    io::stdin().lock(),
+                    io::stdout().lock(),
+                )? {
+                    Session::Finished(corrected) if corrected != text => {
+                        fs::write(&path, corrected)?
                     }
+                    Session::Finished(_) => {}
+                    Session::Quit => break,
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Suggest { word }) if cli.identifiers => {
+            match spell_checker.correct_identifier(&word) {
+                Some(corrected) => {
+                    println!("{corrected}");
+                    Ok(ExitCode::FAILURE)
+                }
+                None => {
+                    println!("{}", word.green());
+                    Ok(ExitCode::SUCCESS)
+                }
+            }
+        }
+        Some(Command::Suggest { word }) => match spell_checker.check_word(&word) {
+            Ok(()) => {
+                println!("{}", word.green());
+                Ok(ExitCode::SUCCESS)
+            }
+            Err(suggestions) => {
+                for suggestion in suggestions.iter().take(cli.max_suggestions) {
+                    println!("{suggestion}");
                 }
+                Ok(ExitCode::FAILURE)
+            }
+        },
+        Some(Command::BuildIndex { output }) => {
+            if paths.len() > 1 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "build-index compiles a single dictionary, but {} were given",
+                        paths.len()
+                    ),
+                ));
+            }
+            spell_checker.dictionaries().main().save_index(&output)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(Command::Query {
+            pattern,
+            fuzzy,
+            prefix,
+        }) => {
+            let trie = spell_checker.dictionaries().main();
+            let prefix = Prefix(&prefix);
+            let mut words = match fuzzy {
+                Some(k) => {
+                    let aut: LevenshteinAutomaton<Deterministic> =
+                        LevenshteinAutomaton::new(&pattern, k).into();
+                    let mut words = trie.filter(&aut.intersection(prefix));
+                    rank_suggestions(&pattern, &mut words);
+                    words
+                }
+                None => {
+                    let aut: PatternAutomaton<NonDeterministic> = PatternAutomaton::new(&pattern);
+                    let aut: PatternAutomaton<Deterministic> = aut.into();
+                    let mut words = trie.filter(&aut.intersection(prefix));
+                    words.sort();
+                    words
+                }
+            };
+            words.dedup();
+            for word in &words {
+                println!("{word}");
+            }
+            Ok(if words.is_empty() {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
             })
-            .collect::<Vec<_>>();
-        println!();
+        }
+        Some(Command::Dot { .. } | Command::Client { .. } | Command::Serve { .. }) => {
+            unreachable!()
+        }
+    }
+}
+
+fn dictionary_paths(cli: &Cli) -> (Vec<PathBuf>, Option<PathBuf>) {
+    let mut paths = cli.dicts.clone();
+    if paths.is_empty() {
+        let env_paths =
+            env::var("LEV_SPELL_CHECK_DICT_PATH").unwrap_or("/usr/share/dict/words".into());
+        paths = env::split_paths(&env_paths).collect();
+    }
+    let personal = cli
+        .personal
+        .clone()
+        .or_else(|| env::var_os("LEV_SPELL_CHECK_PERSONAL_DICT").map(PathBuf::from));
+    (paths, personal)
+}
+
+fn load_dictionaries(paths: &[PathBuf], personal: &Option<PathBuf>) -> io::Result<DictionaryStack> {
+    DictionaryStack::load(paths, personal.as_deref()).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("unable to open dictionary files {paths:?}: {e}"),
+        )
+    })
+}
+
+fn build_spell_checker(
+    cli: &Cli,
+    dictionaries: DictionaryStack,
+) -> io::Result<SpellChecker<impl Corrector + Send + Sync + use<>>> {
+    let mut policy = match cli.degree {
+        Some(k) => EscalationPolicy {
+            start_degree: k,
+            max_degree: k,
+            min_candidates: 1,
+            length_limits: vec![(0, k)],
+            ..EscalationPolicy::default()
+        },
+        None => EscalationPolicy::default(),
+    };
+    if let Some(keyboard) = cli.keyboard {
+        policy.costs = Layout::from(keyboard).edit_costs();
+    }
+    let rules = match cli.rules {
+        true => Transducer::english(),
+        false => Transducer::new(),
+    };
+    let suggester = RuleCorrector::new(rules, policy);
+    let corrector = match &cli.error_model {
+        Some(path) => Ranking::Channel(Box::new(ChannelCorrector::new(
+            suggester,
+            load_channel(path, cli.word_frequencies.as_deref())?,
+        ))),
+        None => Ranking::Edits(suggester),
+    };
+    let mut spell_checker = SpellChecker::from_corrector(dictionaries, corrector)
+        .with_segmenter(Segmenter::default())
+        .with_identifier_splitting(cli.identifiers);
+    if cli.phonetic {
+        spell_checker = spell_checker.with_phonetic();
+    }
+    if cli.compounds {
+        spell_checker = spell_checker.with_compounds(&CompoundConfig {
+            linkers: cli.linkers.clone(),
+            ..CompoundConfig::default()
+        });
+    }
+    Ok(spell_checker.with_cache(cli.cache_size))
+}
+
+fn load_channel(path: &Path, frequencies: Option<&Path>) -> io::Result<NoisyChannel> {
+    let pairs: Vec<(String, String)> = serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut channel = NoisyChannel::new(ErrorModel::train(
+        pairs
+            .iter()
+            .map(|(typo, correct)| (typo.as_str(), correct.as_str())),
+    ));
+    if let Some(path) = frequencies {
+        channel.add_word_frequencies(parse_frequencies(&fs::read_to_string(path)?));
+    }
+    Ok(channel)
+}
+
+enum Ranking {
+    Edits(RuleCorrector<EscalationPolicy>),
+    Channel(Box<ChannelCorrector<RuleCorrector<EscalationPolicy>>>),
+}
+
+impl Corrector for Ranking {
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        match self {
+            Ranking::Edits(corrector) => corrector.candidates(word, tries),
+            Ranking::Channel(corrector) => corrector.candidates(word, tries),
+        }
+    }
+
+    fn score(&self, word: &str, candidate: &str) -> f64 {
+        match self {
+            Ranking::Edits(corrector) => corrector.score(word, candidate),
+            Ranking::Channel(corrector) => corrector.score(word, candidate),
+        }
+    }
+}
+
+fn serve(cli: &Cli, endpoint: &Endpoint, watch: Option<u64>) -> io::Result<ExitCode> {
+    let (paths, personal) = dictionary_paths(cli);
+    let mut watched = paths.clone();
+    watched.extend(personal.clone());
+
+    let settings = cli.clone();
+    let handle = Reloadable::new(move || {
+        build_spell_checker(&settings, load_dictionaries(&paths, &personal)?)
+    })?;
+    let handle = Arc::new(handle.with_watched(&watched));
+    if let Some(seconds) = watch {
+        handle.watch(Duration::from_secs(seconds));
+    }
+
+    match endpoint.port {
+        Some(port) => {
+            let listener = TcpListener::bind(("127.0.0.1", port))?;
+            eprintln!("listening on http://{}", listener.local_addr()?);
+            daemon::serve_http(&handle, listener)?;
+        }
+        #[cfg(unix)]
+        None => {
+            let path = endpoint.socket_path();
+            let listener = bind_socket(&path)?;
+            eprintln!("listening on {}", path.display());
+            daemon::serve_unix(&handle, listener)?;
+        }
+        #[cfg(not(unix))]
+        None => return Err(unix_sockets_unsupported()),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(unix)]
+fn bind_socket(path: &Path) -> io::Result<UnixListener> {
+    match UnixListener::bind(path) {
+        Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+            if !fs::symlink_metadata(path)?.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("address in use: {} is not a socket", path.display()),
+                ));
+            }
+            if UnixStream::connect(path).is_ok() {
+                return Err(e);
+            }
+            fs::remove_file(path)?;
+            UnixListener::bind(path)
+        }
+        res => res,
+    }
+}
+
+#[cfg(not(unix))]
+fn unix_sockets_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "unix sockets are not supported on this platform, use --port",
+    )
+}
+
+fn client(
+    endpoint: &Endpoint,
+    request: &ClientRequest,
+    max_suggestions: usize,
+) -> io::Result<ExitCode> {
+    let mut failure = false;
+    match request {
+        ClientRequest::Check { inputs } => {
+            for source in expand_inputs(inputs)? {
+                let text = read_source(source.as_deref())?;
+                let response = endpoint.request(&json!({ "method": "check", "text": text }))?;
+                let name = source.map_or("-".into(), |p| p.display().to_string());
+                for m in response["misspellings"].as_array().into_iter().flatten() {
+                    failure = true;
+                    let suggestions: Vec<_> = m["suggestions"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str)
+                        .take(max_suggestions)
+                        .collect();
+                    println!(
+                        "{name}:{}:{}: {} -> {}",
+                        m["line"],
+                        m["column"],
+                        m["word"].as_str().unwrap_or_default().red(),
+                        suggestions.join(", ").green()
+                    );
+                }
+            }
+        }
+        ClientRequest::Suggest { word } => {
+            let response = endpoint.request(&json!({ "method": "suggest", "word": word }))?;
+            failure = response["correct"] != true;
+            let suggestions = response["suggestions"].as_array().into_iter().flatten();
+            for suggestion in suggestions.take(max_suggestions).filter_map(Value::as_str) {
+                println!("{suggestion}");
+            }
+        }
+        ClientRequest::FuzzyQuery { word, distance } => {
+            let response = endpoint.request(&json!({
+                "method": "fuzzy-query",
+                "word": word,
+                "distance": distance,
+            }))?;
+            let words: Vec<_> = response["words"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            for word in &words {
+                println!("{word}");
+            }
+            failure = words.is_empty();
+        }
+    }
+
+    Ok(if failure {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+enum CorrectMode {
+    Stdout,
+    InPlace { backup_suffix: String },
+    Diff,
+}
+
+fn correct<F>(
+    spell_checker: &SpellChecker<F>,
+    inputs: &[String],
+    filter: FilterChoice,
+    mode: &CorrectMode,
+    min_confidence: Option<f64>,
+) -> io::Result<ExitCode>
+where
+    F: Corrector,
+{
+    let mut corrected_any = false;
+    for source in &expand_inputs(inputs)? {
+        let text = read_source(source.as_deref())?;
+        let (corrected, corrections) =
+            spell_checker.autocorrect(&text, filter.select(source.as_deref()), min_confidence)?;
+        corrected_any |= !corrections.is_empty();
+
+        match (mode, source) {
+            (CorrectMode::Stdout, _) => print!("{corrected}"),
+            (CorrectMode::Diff, _) => {
+                let name = source
+                    .as_ref()
+                    .map_or("-".into(), |p| p.display().to_string());
+                print!("{}", unified_diff(&name, &name, &text, &corrected, 3));
+            }
+            (CorrectMode::InPlace { .. }, None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "cannot correct stdin in place",
+                ));
+            }
+            (CorrectMode::InPlace { backup_suffix }, Some(path)) => {
+                if corrections.is_empty() {
+                    continue;
+                }
+                if !backup_suffix.is_empty() {
+                    let mut backup = path.clone().into_os_string();
+                    backup.push(backup_suffix);
+                    fs::copy(path, backup)?;
+                }
+                fs::write(path, corrected)?;
+                for correction in &corrections {
+                    eprintln!(
+                        "{}:{}:{}: {} -> {}",
+                        path.display(),
+                        correction.line,
+                        correction.column,
+                        correction.original,
+                        correction.replacement
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(
+        if corrected_any && !matches!(mode, CorrectMode::InPlace { .. }) {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        },
+    )
+}
+
+fn expand_inputs(inputs: &[String]) -> io::Result<Vec<Option<PathBuf>>> {
+    if inputs.is_empty() {
+        return Ok(vec![None]);
+    }
+
+    let mut sources = vec![];
+    for input in inputs {
+        if input == "-" {
+            sources.push(None);
+        } else if input.contains(['*', '?', '[']) {
+            let paths =
+                glob::glob(input).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let matched = sources.len();
+            for path in paths {
+                sources.push(Some(path.map_err(io::Error::from)?));
+            }
+            if sources.len() == matched {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no files match {input}"),
+                ));
+            }
+        } else {
+            sources.push(Some(PathBuf::from(input)));
+        }
+    }
+    Ok(sources)
+}
+
+fn read_source(source: Option<&Path>) -> io::Result<String> {
+    match source {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+    }
+}
+
+fn check<F>(
+    spell_checker: &SpellChecker<F>,
+    inputs: &[String],
+    filter: FilterChoice,
+    format: OutputFormat,
+    max_suggestions: usize,
+) -> io::Result<ExitCode>
+where
+    F: Corrector,
+{
+    let sources = expand_inputs(inputs)?;
+    let mut reports = vec![];
+    for source in &sources {
+        let text = read_source(source.as_deref())?;
+        let file = source
+            .as_ref()
+            .map_or("-".into(), |p| p.display().to_string());
+        let misspellings = spell_checker.check_filtered(&text, filter.select(source.as_deref()))?;
+
+        if format == OutputFormat::Text {
+            if sources.len() > 1 {
+                println!("{}", format!("==> {file} <==").bold());
+            }
+            print_colored(&text, &misspellings, max_suggestions);
+        }
+        reports.push(FileReport { file, misspellings });
+    }
+
+    let format = match format {
+        OutputFormat::Text => None,
+        OutputFormat::Json => Some(Format::Json),
+        OutputFormat::Jsonl => Some(Format::Jsonl),
+        OutputFormat::Sarif => Some(Format::Sarif),
+        OutputFormat::Checkstyle => Some(Format::Checkstyle),
+        OutputFormat::Tsv => Some(Format::Tsv),
+    };
+    if let Some(format) = format {
+        write_report(&mut io::stdout().lock(), format, &reports, max_suggestions)?;
+    }
+
+    Ok(if reports.iter().any(|r| !r.misspellings.is_empty()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn print_colored(text: &str, misspellings: &[Misspelling], max_suggestions: usize) {
+    let mut printed_until = 0;
+    for misspelling in misspellings {
+        print!("{}", &text[printed_until..misspelling.byte_range.start]);
+        printed_until = misspelling.byte_range.end;
+
+        let suggestions = &misspelling.suggestions;
+        let suggestions = &suggestions[..suggestions.len().min(max_suggestions)];
+        print!("{}", misspelling.word.red().strikethrough());
+        match suggestions.len() {
+            0 => {}
+            1 => {
+                print!(" -> {}", suggestions[0].green().italic())
+            }
+            _ => {
+                print!(" -> {{ ");
+                for (i, correction) in suggestions.iter().enumerate() {
+                    print!("{}", correction.green());
+                    if i != suggestions.len() - 1 {
+                        print!(", ")
+                    }
+                }
+                print!(" }}");
+            }
+        }
     }
+    print!("{}", &text[printed_until..]);
 }
@@ -0,0 +1,188 @@
+pub trait Matcher {
+    type State: Clone;
+
+    fn start(&self) -> Self::State;
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State;
+
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    fn can_match(&self, _state: &Self::State) -> bool {
+        true
+    }
+
+    fn intersection<M: Matcher>(self, other: M) -> Intersection<Self, M>
+    where
+        Self: Sized,
+    {
+        Intersection(self, other)
+    }
+
+    fn union<M: Matcher>(self, other: M) -> Union<Self, M>
+    where
+        Self: Sized,
+    {
+        Union(self, other)
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for &M {
+    type State = M::State;
+
+    fn start(&self) -> Self::State {
+        (**self).start()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        (**self).accept(state, c)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        (**self).is_match(state)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        (**self).can_match(state)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Prefix<'a>(pub &'a str);
+
+impl Matcher for Prefix<'_> {
+    type State = Option<usize>;
+
+    fn start(&self) -> Self::State {
+        Some(0)
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        let matched = (*state)?;
+        match self.0[matched..].chars().next() {
+            Some(expected) if expected == c => Some(matched + c.len_utf8()),
+            Some(_) => None,
+            None => Some(matched),
+        }
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        *state == Some(self.0.len())
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Intersection<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for Intersection<A, B> {
+    type State = (A::State, B::State);
+
+    fn start(&self) -> Self::State {
+        (self.0.start(), self.1.start())
+    }
+
+    fn accept(&self, (a, b): &Self::State, c: char) -> Self::State {
+        (self.0.accept(a, c), self.1.accept(b, c))
+    }
+
+    fn is_match(&self, (a, b): &Self::State) -> bool {
+        self.0.is_match(a) && self.1.is_match(b)
+    }
+
+    fn can_match(&self, (a, b): &Self::State) -> bool {
+        self.0.can_match(a) && self.1.can_match(b)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Union<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for Union<A, B> {
+    type State = (A::State, B::State);
+
+    fn start(&self) -> Self::State {
+        (self.0.start(), self.1.start())
+    }
+
+    fn accept(&self, (a, b): &Self::State, c: char) -> Self::State {
+        (self.0.accept(a, c), self.1.accept(b, c))
+    }
+
+    fn is_match(&self, (a, b): &Self::State) -> bool {
+        self.0.is_match(a) || self.1.is_match(b)
+    }
+
+    fn can_match(&self, (a, b): &Self::State) -> bool {
+        self.0.can_match(a) || self.1.can_match(b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        automaton::{Deterministic, NonDeterministic},
+        levenshtein_automaton::LevenshteinAutomaton,
+        matcher::{Matcher, Prefix},
+        pattern::PatternAutomaton,
+        trie::Trie,
+    };
+
+    struct ContainsDigit;
+
+    impl Matcher for ContainsDigit {
+        type State = bool;
+
+        fn start(&self) -> bool {
+            false
+        }
+
+        fn accept(&self, seen: &bool, c: char) -> bool {
+            *seen || c.is_ascii_digit()
+        }
+
+        fn is_match(&self, seen: &bool) -> bool {
+            *seen
+        }
+    }
+
+    #[test]
+    fn test_matchers() {
+        let trie = Trie::new(&mut [
+            "bin", "bing", "bingo", "bong", "b1ng", "r2d2", "ring", "héllo", "hélium",
+        ]);
+        let sorted = |mut words: Vec<String>| {
+            words.sort();
+            words
+        };
+
+        assert_eq!(
+            sorted(trie.filter(&Prefix("bin"))),
+            vec!["bin", "bing", "bingo"]
+        );
+        assert_eq!(sorted(trie.filter(&Prefix("hé"))), vec!["hélium", "héllo"]);
+        assert_eq!(sorted(trie.filter(&Prefix(""))).len(), 9);
+
+        let nfa = LevenshteinAutomaton::new("bing", 1);
+        let lazy = sorted(trie.filter(&nfa));
+        assert_eq!(lazy, vec!["b1ng", "bin", "bing", "bingo", "bong", "ring"]);
+        let dfa: LevenshteinAutomaton<Deterministic> = nfa.into();
+        assert_eq!(sorted(trie.filter(&dfa)), lazy);
+        assert_eq!(sorted(trie.filter(dfa.get_automaton())), lazy);
+
+        assert_eq!(
+            sorted(trie.filter(&(&dfa).intersection(ContainsDigit))),
+            vec!["b1ng"]
+        );
+        assert_eq!(sorted(trie.filter(&ContainsDigit)), vec!["b1ng", "r2d2"]);
+
+        let pattern: PatternAutomaton<NonDeterministic> = PatternAutomaton::new("*ng");
+        let either = pattern.union(Prefix("hé"));
+        assert_eq!(
+            sorted(trie.filter(&either)),
+            vec!["b1ng", "bing", "bong", "hélium", "héllo", "ring"]
+        );
+    }
+}
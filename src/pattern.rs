@@ -0,0 +1,92 @@
+use crate::{
+    automaton::{Automaton, Deterministic, NonDeterministic, Transition},
+    matcher::Matcher,
+};
+
+#[derive(Debug)]
+pub struct PatternAutomaton<T>(Automaton<T>);
+
+impl<T> PatternAutomaton<T> {
+    pub fn get_automaton(&self) -> &Automaton<T> {
+        &self.0
+    }
+}
+
+impl PatternAutomaton<NonDeterministic> {
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        let mut automaton: Automaton<NonDeterministic> = Automaton::default();
+        let mut state = automaton.add_state();
+
+        for c in pattern.chars() {
+            match c {
+                '*' => automaton.add_transition(state, state, Transition::Star),
+                '?' => {
+                    let next = automaton.add_state();
+                    automaton.add_transition(state, next, Transition::Star);
+                    state = next;
+                }
+                c => {
+                    let next = automaton.add_state();
+                    automaton.add_transition(state, next, Transition::Is(c));
+                    state = next;
+                }
+            }
+        }
+        automaton.make_state_final(state);
+
+        Self(automaton)
+    }
+}
+
+impl From<PatternAutomaton<NonDeterministic>> for PatternAutomaton<Deterministic> {
+    fn from(nfa: PatternAutomaton<NonDeterministic>) -> Self {
+        Self(nfa.0.into())
+    }
+}
+
+impl<T> Matcher for PatternAutomaton<T>
+where
+    Automaton<T>: Matcher,
+{
+    type State = <Automaton<T> as Matcher>::State;
+
+    fn start(&self) -> Self::State {
+        self.0.start()
+    }
+
+    fn accept(&self, state: &Self::State, c: char) -> Self::State {
+        self.0.accept(state, c)
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        self.0.is_match(state)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        self.0.can_match(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{automaton::Deterministic, pattern::PatternAutomaton, trie::Trie};
+
+    #[test]
+    fn test_pattern_query() {
+        let trie = Trie::new(&mut ["bin", "bing", "bong", "bingo", "ring"]);
+
+        let query = |pattern| {
+            let aut: PatternAutomaton<Deterministic> = PatternAutomaton::new(pattern).into();
+            let mut words = trie.filter(aut.get_automaton());
+            words.sort();
+            words
+        };
+
+        assert_eq!(query("b?ng"), vec!["bing", "bong"]);
+        assert_eq!(query("*ing"), vec!["bing", "ring"]);
+        assert_eq!(query("bin*"), vec!["bin", "bing", "bingo"]);
+        assert_eq!(query("*o*"), vec!["bingo", "bong"]);
+        assert!(query("x*").is_empty());
+    }
+}
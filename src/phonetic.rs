@@ -0,0 +1,680 @@
+use std::collections::HashMap;
+
+use crate::{matcher::Prefix, spell_checker::rank_suggestions, trie::Trie};
+
+const MAX_KEY_LEN: usize = 4;
+
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let mut encoder = Encoder::new(word);
+    encoder.encode();
+    (encoder.primary, encoder.alternate)
+}
+
+struct Encoder {
+    chars: Vec<char>,
+    primary: String,
+    alternate: String,
+    slavo_germanic: bool,
+}
+
+impl Encoder {
+    fn new(word: &str) -> Self {
+        let chars: Vec<char> = word.trim().to_uppercase().chars().collect();
+        let text: String = chars.iter().collect();
+        Self {
+            slavo_germanic: text.contains('W')
+                || text.contains('K')
+                || text.contains("CZ")
+                || text.contains("WITZ"),
+            chars,
+            primary: String::new(),
+            alternate: String::new(),
+        }
+    }
+
+    fn len(&self) -> isize {
+        self.chars.len() as isize
+    }
+
+    fn char_at(&self, i: isize) -> char {
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.chars.get(i))
+            .copied()
+            .unwrap_or('\0')
+    }
+
+    fn at(&self, start: isize, len: usize, options: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else {
+            return false;
+        };
+        let Some(slice) = self.chars.get(start..start + len) else {
+            return false;
+        };
+        options
+            .iter()
+            .any(|option| option.chars().eq(slice.iter().copied()))
+    }
+
+    fn is_vowel(&self, i: isize) -> bool {
+        matches!(self.char_at(i), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    fn add(&mut self, primary: &str, alternate: &str) {
+        self.add_primary(primary);
+        self.add_alternate(alternate);
+    }
+
+    fn add_both(&mut self, code: &str) {
+        self.add(code, code);
+    }
+
+    fn add_primary(&mut self, code: &str) {
+        let room = MAX_KEY_LEN.saturating_sub(self.primary.len());
+        self.primary.extend(code.chars().take(room));
+    }
+
+    fn add_alternate(&mut self, code: &str) {
+        let room = MAX_KEY_LEN.saturating_sub(self.alternate.len());
+        self.alternate.extend(code.chars().take(room));
+    }
+
+    fn skip_double(&self, i: isize, c: char) -> isize {
+        if self.char_at(i + 1) == c {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn encode(&mut self) {
+        let mut i = match self.at(0, 2, &["GN", "KN", "PN", "WR", "PS"]) {
+            true => 1,
+            false => 0,
+        };
+
+        while (self.primary.len() < MAX_KEY_LEN || self.alternate.len() < MAX_KEY_LEN)
+            && i < self.len()
+        {
+            i = match self.char_at(i) {
+                'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                    if i == 0 {
+                        self.add_both("A");
+                    }
+                    i + 1
+                }
+                'B' => {
+                    self.add_both("P");
+                    self.skip_double(i, 'B')
+                }
+                'Ç' => {
+                    self.add_both("S");
+                    i + 1
+                }
+                'C' => self.c(i),
+                'D' => self.d(i),
+                'F' => {
+                    self.add_both("F");
+                    self.skip_double(i, 'F')
+                }
+                'G' => self.g(i),
+                'H' => self.h(i),
+                'J' => self.j(i),
+                'K' => {
+                    self.add_both("K");
+                    self.skip_double(i, 'K')
+                }
+                'L' => self.l(i),
+                'M' => {
+                    self.add_both("M");
+                    let umb = self.at(i - 1, 3, &["UMB"])
+                        && (i + 1 == self.len() - 1 || self.at(i + 2, 2, &["ER"]));
+                    if self.char_at(i + 1) == 'M' || umb {
+                        i + 2
+                    } else {
+                        i + 1
+                    }
+                }
+                'N' => {
+                    self.add_both("N");
+                    self.skip_double(i, 'N')
+                }
+                'Ñ' => {
+                    self.add_both("N");
+                    i + 1
+                }
+                'P' => self.p(i),
+                'Q' => {
+                    self.add_both("K");
+                    self.skip_double(i, 'Q')
+                }
+                'R' => self.r(i),
+                'S' => self.s(i),
+                'T' => self.t(i),
+                'V' => {
+                    self.add_both("F");
+                    self.skip_double(i, 'V')
+                }
+                'W' => self.w(i),
+                'X' => self.x(i),
+                'Z' => self.z(i),
+                _ => i + 1,
+            };
+        }
+    }
+
+    fn c(&mut self, i: isize) -> isize {
+        if self.c_is_k(i) {
+            self.add_both("K");
+            i + 2
+        } else if i == 0 && self.at(i, 6, &["CAESAR"]) {
+            self.add_both("S");
+            i + 2
+        } else if self.at(i, 2, &["CH"]) {
+            self.ch(i)
+        } else if self.at(i, 2, &["CZ"]) && !self.at(i - 2, 4, &["WICZ"]) {
+            self.add("S", "X");
+            i + 2
+        } else if self.at(i + 1, 3, &["CIA"]) {
+            self.add_both("X");
+            i + 3
+        } else if self.at(i, 2, &["CC"]) && !(i == 1 && self.char_at(0) == 'M') {
+            if self.at(i + 2, 1, &["I", "E", "H"]) && !self.at(i + 2, 2, &["HU"]) {
+                if (i == 1 && self.char_at(i - 1) == 'A') || self.at(i - 1, 5, &["UCCEE", "UCCES"])
+                {
+                    self.add_both("KS");
+                } else {
+                    self.add_both("X");
+                }
+                i + 3
+            } else {
+                self.add_both("K");
+                i + 2
+            }
+        } else if self.at(i, 2, &["CK", "CG", "CQ"]) {
+            self.add_both("K");
+            i + 2
+        } else if self.at(i, 2, &["CI", "CE", "CY"]) {
+            if self.at(i, 3, &["CIO", "CIE", "CIA"]) {
+                self.add("S", "X");
+            } else {
+                self.add_both("S");
+            }
+            i + 2
+        } else {
+            self.add_both("K");
+            if self.at(i + 1, 2, &[" C", " Q", " G"]) {
+                i + 3
+            } else if self.at(i + 1, 1, &["C", "K", "Q"]) && !self.at(i + 1, 2, &["CE", "CI"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn c_is_k(&self, i: isize) -> bool {
+        if self.at(i, 4, &["CHIA"]) {
+            return true;
+        }
+        if i <= 1 || self.is_vowel(i - 2) || !self.at(i - 1, 3, &["ACH"]) {
+            return false;
+        }
+        let c = self.char_at(i + 2);
+        (c != 'I' && c != 'E') || self.at(i - 2, 6, &["BACHER", "MACHER"])
+    }
+
+    fn ch(&mut self, i: isize) -> isize {
+        let greek = i == 0
+            && (self.at(i + 1, 5, &["HARAC", "HARIS"])
+                || self.at(i + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+            && !self.at(0, 5, &["CHORE"]);
+        let germanic = self.at(0, 4, &["VAN ", "VON "])
+            || self.at(0, 3, &["SCH"])
+            || self.at(i - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+            || self.at(i + 2, 1, &["T", "S"])
+            || ((self.at(i - 1, 1, &["A", "O", "U", "E"]) || i == 0)
+                && (self.at(
+                    i + 2,
+                    1,
+                    &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+                ) || i + 1 == self.len() - 1));
+
+        if i > 0 && self.at(i, 4, &["CHAE"]) {
+            self.add("K", "X");
+        } else if greek || germanic {
+            self.add_both("K");
+        } else if i > 0 {
+            if self.at(0, 2, &["MC"]) {
+                self.add_both("K");
+            } else {
+                self.add("X", "K");
+            }
+        } else {
+            self.add_both("X");
+        }
+        i + 2
+    }
+
+    fn d(&mut self, i: isize) -> isize {
+        if self.at(i, 2, &["DG"]) {
+            if self.at(i + 2, 1, &["I", "E", "Y"]) {
+                self.add_both("J");
+                i + 3
+            } else {
+                self.add_both("TK");
+                i + 2
+            }
+        } else if self.at(i, 2, &["DT", "DD"]) {
+            self.add_both("T");
+            i + 2
+        } else {
+            self.add_both("T");
+            i + 1
+        }
+    }
+
+    fn g(&mut self, i: isize) -> isize {
+        let next = self.char_at(i + 1);
+        if next == 'H' {
+            self.gh(i)
+        } else if next == 'N' {
+            if i == 1 && self.is_vowel(0) && !self.slavo_germanic {
+                self.add("KN", "N");
+            } else if !self.at(i + 2, 2, &["EY"]) && !self.slavo_germanic {
+                self.add("N", "KN");
+            } else {
+                self.add_both("KN");
+            }
+            i + 2
+        } else if self.at(i + 1, 2, &["LI"]) && !self.slavo_germanic {
+            self.add("KL", "L");
+            i + 2
+        } else if self.g_is_k_or_j(i) {
+            self.add("K", "J");
+            i + 2
+        } else if self.at(i + 1, 1, &["E", "I", "Y"]) || self.at(i - 1, 4, &["AGGI", "OGGI"]) {
+            if self.at(0, 4, &["VAN ", "VON "])
+                || self.at(0, 3, &["SCH"])
+                || self.at(i + 1, 2, &["ET"])
+            {
+                self.add_both("K");
+            } else if self.at(i + 1, 3, &["IER"]) {
+                self.add_both("J");
+            } else {
+                self.add("J", "K");
+            }
+            i + 2
+        } else {
+            self.add_both("K");
+            self.skip_double(i, 'G')
+        }
+    }
+
+    fn g_is_k_or_j(&self, i: isize) -> bool {
+        let next = self.char_at(i + 1);
+        let initial = i == 0
+            && (next == 'Y'
+                || self.at(
+                    i + 1,
+                    2,
+                    &[
+                        "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                    ],
+                ));
+        initial
+            || ((self.at(i + 1, 2, &["ER"]) || next == 'Y')
+                && !self.at(0, 6, &["DANGER", "RANGER", "MANGER"])
+                && !self.at(i - 1, 1, &["E", "I"])
+                && !self.at(i - 1, 3, &["RGY", "OGY"]))
+    }
+
+    fn gh(&mut self, i: isize) -> isize {
+        if i > 0 && !self.is_vowel(i - 1) {
+            self.add_both("K");
+        } else if i == 0 {
+            match self.char_at(i + 2) {
+                'I' => self.add_both("J"),
+                _ => self.add_both("K"),
+            }
+        } else {
+            let silent = (i > 1 && self.at(i - 2, 1, &["B", "H", "D"]))
+                || (i > 2 && self.at(i - 3, 1, &["B", "H", "D"]))
+                || (i > 3 && self.at(i - 4, 1, &["B", "H"]));
+            if !silent
+                && i > 2
+                && self.char_at(i - 1) == 'U'
+                && self.at(i - 3, 1, &["C", "G", "L", "R", "T"])
+            {
+                self.add_both("F");
+            } else if !silent && self.char_at(i - 1) != 'I' {
+                self.add_both("K");
+            }
+        }
+        i + 2
+    }
+
+    fn h(&mut self, i: isize) -> isize {
+        if (i == 0 || self.is_vowel(i - 1)) && self.is_vowel(i + 1) {
+            self.add_both("H");
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn j(&mut self, i: isize) -> isize {
+        if self.at(i, 4, &["JOSE"]) || self.at(0, 4, &["SAN "]) {
+            if (i == 0 && self.char_at(i + 4) == ' ') || self.len() == 4 || self.at(0, 4, &["SAN "])
+            {
+                self.add_both("H");
+            } else {
+                self.add("J", "H");
+            }
+            return i + 1;
+        }
+
+        if i == 0 {
+            self.add("J", "A");
+        } else if self.is_vowel(i - 1)
+            && !self.slavo_germanic
+            && matches!(self.char_at(i + 1), 'A' | 'O')
+        {
+            self.add("J", "H");
+        } else if i == self.len() - 1 {
+            self.add_primary("J");
+        } else if !self.at(i + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+            && !self.at(i - 1, 1, &["S", "K", "L"])
+        {
+            self.add_both("J");
+        }
+        self.skip_double(i, 'J')
+    }
+
+    fn l(&mut self, i: isize) -> isize {
+        if self.char_at(i + 1) != 'L' {
+            self.add_both("L");
+            return i + 1;
+        }
+
+        let len = self.len();
+        let spanish = (i == len - 3 && self.at(i - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+            || ((self.at(len - 2, 2, &["AS", "OS"]) || self.at(len - 1, 1, &["A", "O"]))
+                && self.at(i - 1, 4, &["ALLE"]));
+        if spanish {
+            self.add_primary("L");
+        } else {
+            self.add_both("L");
+        }
+        i + 2
+    }
+
+    fn p(&mut self, i: isize) -> isize {
+        if self.char_at(i + 1) == 'H' {
+            self.add_both("F");
+            i + 2
+        } else {
+            self.add_both("P");
+            if self.at(i + 1, 1, &["P", "B"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn r(&mut self, i: isize) -> isize {
+        if i == self.len() - 1
+            && !self.slavo_germanic
+            && self.at(i - 2, 2, &["IE"])
+            && !self.at(i - 4, 2, &["ME", "MA"])
+        {
+            self.add_alternate("R");
+        } else {
+            self.add_both("R");
+        }
+        self.skip_double(i, 'R')
+    }
+
+    fn s(&mut self, i: isize) -> isize {
+        if self.at(i - 1, 3, &["ISL", "YSL"]) {
+            i + 1
+        } else if i == 0 && self.at(i, 5, &["SUGAR"]) {
+            self.add("X", "S");
+            i + 1
+        } else if self.at(i, 2, &["SH"]) {
+            if self.at(i + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                self.add_both("S");
+            } else {
+                self.add_both("X");
+            }
+            i + 2
+        } else if self.at(i, 3, &["SIO", "SIA"]) || self.at(i, 4, &["SIAN"]) {
+            if self.slavo_germanic {
+                self.add_both("S");
+            } else {
+                self.add("S", "X");
+            }
+            i + 3
+        } else if (i == 0 && self.at(i + 1, 1, &["M", "N", "L", "W"])) || self.at(i + 1, 1, &["Z"])
+        {
+            self.add("S", "X");
+            self.skip_double(i, 'Z')
+        } else if self.at(i, 2, &["SC"]) {
+            self.sc(i)
+        } else {
+            if i == self.len() - 1 && self.at(i - 2, 2, &["AI", "OI"]) {
+                self.add_alternate("S");
+            } else {
+                self.add_both("S");
+            }
+            if self.at(i + 1, 1, &["S", "Z"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn sc(&mut self, i: isize) -> isize {
+        if self.char_at(i + 2) == 'H' {
+            if self.at(i + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                if self.at(i + 3, 2, &["ER", "EN"]) {
+                    self.add("X", "SK");
+                } else {
+                    self.add_both("SK");
+                }
+            } else if i == 0 && !self.is_vowel(3) && self.char_at(3) != 'W' {
+                self.add("X", "S");
+            } else {
+                self.add_both("X");
+            }
+        } else if self.at(i + 2, 1, &["I", "E", "Y"]) {
+            self.add_both("S");
+        } else {
+            self.add_both("SK");
+        }
+        i + 3
+    }
+
+    fn t(&mut self, i: isize) -> isize {
+        if self.at(i, 4, &["TION"]) || self.at(i, 3, &["TIA", "TCH"]) {
+            self.add_both("X");
+            i + 3
+        } else if self.at(i, 2, &["TH"]) || self.at(i, 3, &["TTH"]) {
+            if self.at(i + 2, 2, &["OM", "AM"])
+                || self.at(0, 4, &["VAN ", "VON "])
+                || self.at(0, 3, &["SCH"])
+            {
+                self.add_both("T");
+            } else {
+                self.add("0", "T");
+            }
+            i + 2
+        } else {
+            self.add_both("T");
+            if self.at(i + 1, 1, &["T", "D"]) {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn w(&mut self, i: isize) -> isize {
+        if self.at(i, 2, &["WR"]) {
+            self.add_both("R");
+            return i + 2;
+        }
+
+        if i == 0 && (self.is_vowel(i + 1) || self.at(i, 2, &["WH"])) {
+            if self.is_vowel(i + 1) {
+                self.add("A", "F");
+            } else {
+                self.add_both("A");
+            }
+            i + 1
+        } else if (i == self.len() - 1 && self.is_vowel(i - 1))
+            || self.at(i - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+            || self.at(0, 3, &["SCH"])
+        {
+            self.add_alternate("F");
+            i + 1
+        } else if self.at(i, 4, &["WICZ", "WITZ"]) {
+            self.add("TS", "FX");
+            i + 4
+        } else {
+            i + 1
+        }
+    }
+
+    fn x(&mut self, i: isize) -> isize {
+        if i == 0 {
+            self.add_both("S");
+            return i + 1;
+        }
+
+        let french = i == self.len() - 1
+            && (self.at(i - 3, 3, &["IAU", "EAU"]) || self.at(i - 2, 2, &["AU", "OU"]));
+        if !french {
+            self.add_both("KS");
+        }
+        if self.at(i + 1, 1, &["C", "X"]) {
+            i + 2
+        } else {
+            i + 1
+        }
+    }
+
+    fn z(&mut self, i: isize) -> isize {
+        if self.char_at(i + 1) == 'H' {
+            self.add_both("J");
+            return i + 2;
+        }
+
+        if self.at(i + 1, 2, &["ZO", "ZI", "ZA"])
+            || (self.slavo_germanic && i > 0 && self.char_at(i - 1) != 'T')
+        {
+            self.add("S", "TS");
+        } else {
+            self.add_both("S");
+        }
+        self.skip_double(i, 'Z')
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhoneticIndex {
+    keys: HashMap<String, Vec<String>>,
+}
+
+impl PhoneticIndex {
+    pub fn new(trie: &Trie) -> Self {
+        Self::from_words(trie.filter(&Prefix("")))
+    }
+
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut keys: HashMap<String, Vec<String>> = HashMap::new();
+        for word in words {
+            let word = word.into();
+            let (primary, alternate) = double_metaphone(&word);
+            if !alternate.is_empty() && alternate != primary {
+                keys.entry(alternate).or_default().push(word.clone());
+            }
+            if !primary.is_empty() {
+                keys.entry(primary).or_default().push(word);
+            }
+        }
+        for words in keys.values_mut() {
+            words.sort_unstable();
+            words.dedup();
+        }
+        Self { keys }
+    }
+
+    pub fn lookup(&self, word: &str) -> Vec<String> {
+        let (primary, alternate) = double_metaphone(word);
+        let mut candidates: Vec<String> = [primary, alternate]
+            .iter()
+            .filter_map(|key| self.keys.get(key))
+            .flatten()
+            .cloned()
+            .collect();
+        rank_suggestions(word, &mut candidates);
+        candidates.dedup();
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        phonetic::{PhoneticIndex, double_metaphone},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_double_metaphone() {
+        let encode = double_metaphone;
+
+        assert_eq!(encode("Smith"), ("SM0".into(), "XMT".into()));
+        assert_eq!(encode("Schmidt"), ("XMT".into(), "SMT".into()));
+        assert_eq!(encode("knight"), ("NT".into(), "NT".into()));
+        assert_eq!(encode("Caesar"), ("SSR".into(), "SSR".into()));
+        assert_eq!(encode("Xavier"), ("SF".into(), "SFR".into()));
+        assert_eq!(encode("phone"), encode("fone"));
+        assert_eq!(encode("Jose"), ("HS".into(), "HS".into()));
+        assert_eq!(encode(""), (String::new(), String::new()));
+    }
+
+    #[test]
+    fn test_phonetic_index() {
+        let trie = Trie::new(&mut [
+            "phone",
+            "fun",
+            "knight",
+            "night",
+            "nation",
+            "tough",
+            "knowledge",
+            "physician",
+            "psychology",
+        ]);
+        let index = PhoneticIndex::new(&trie);
+
+        assert_eq!(index.lookup("fone"), vec!["fun", "phone"]);
+        assert_eq!(index.lookup("nite"), vec!["night", "knight"]);
+        assert_eq!(index.lookup("tuff"), vec!["tough"]);
+        assert_eq!(index.lookup("nashun"), vec!["nation"]);
+        assert_eq!(index.lookup("nollij"), vec!["knowledge"]);
+        assert_eq!(index.lookup("fizishun"), vec!["physician"]);
+        assert_eq!(index.lookup("sikology"), vec!["psychology"]);
+
+        let index = PhoneticIndex::from_words(["night", "night"]);
+        assert_eq!(index.lookup("nite"), vec!["night"]);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fs, io,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use crate::dictionary::DictionaryStack;
+
+type Loader<T> = Box<dyn Fn() -> io::Result<T> + Send + Sync>;
+
+pub struct Reloadable<T> {
+    current: RwLock<Arc<T>>,
+    loader: Loader<T>,
+    watched: Vec<PathBuf>,
+    mtimes: Mutex<Vec<Option<SystemTime>>>,
+    generation: AtomicUsize,
+}
+
+impl<T> Reloadable<T> {
+    pub fn new<L>(loader: L) -> io::Result<Self>
+    where
+        L: Fn() -> io::Result<T> + Send + Sync + 'static,
+    {
+        Ok(Self {
+            current: RwLock::new(Arc::new(loader()?)),
+            loader: Box::new(loader),
+            watched: vec![],
+            mtimes: Mutex::new(vec![]),
+            generation: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn with_watched(mut self, paths: &[PathBuf]) -> Self {
+        self.watched = paths.to_vec();
+        self.mtimes = Mutex::new(modification_times(&self.watched));
+        self
+    }
+
+    pub fn snapshot(&self) -> Arc<T> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub fn reload(&self) -> io::Result<()> {
+        let mtimes = modification_times(&self.watched);
+        let value = Arc::new((self.loader)()?);
+
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = value;
+        *self.mtimes.lock().unwrap_or_else(|e| e.into_inner()) = mtimes;
+        self.generation.fetch_add(1, Ordering::Release);
+        Ok(())
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        let res = f(Arc::get_mut(&mut current)?);
+        *self.mtimes.lock().unwrap_or_else(|e| e.into_inner()) = modification_times(&self.watched);
+        Some(res)
+    }
+
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let changed = modification_times(&self.watched)
+            != *self.mtimes.lock().unwrap_or_else(|e| e.into_inner());
+        if changed {
+            self.reload()?;
+        }
+        Ok(changed)
+    }
+}
+
+impl<T> Reloadable<T>
+where
+    T: Send + Sync + 'static,
+{
+    pub fn reload_in_background(self: &Arc<Self>) -> JoinHandle<io::Result<()>> {
+        let handle = Arc::clone(self);
+        thread::spawn(move || handle.reload())
+    }
+
+    pub fn watch(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let handle = Arc::downgrade(self);
+        thread::spawn(move || {
+            loop {
+                thread::sleep(interval);
+                let Some(handle) = handle.upgrade() else {
+                    break;
+                };
+                if let Err(e) = handle.reload_if_changed() {
+                    eprintln!("lev-automaton: reloading dictionaries failed: {e}");
+                }
+            }
+        })
+    }
+}
+
+impl Reloadable<DictionaryStack> {
+    pub fn load(paths: Vec<PathBuf>, personal: Option<PathBuf>) -> io::Result<Self> {
+        let mut watched = paths.clone();
+        watched.extend(personal.clone());
+
+        let handle = Self::new(move || DictionaryStack::load(&paths, personal.as_deref()))?;
+        Ok(handle.with_watched(&watched))
+    }
+}
+
+fn modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        env, fs,
+        fs::File,
+        process,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
+
+    use crate::{dictionary::DictionaryStack, reload::Reloadable};
+
+    #[test]
+    fn test_reload() {
+        let path = env::temp_dir().join(format!("lev-automaton-reload-{}.txt", process::id()));
+        fs::write(&path, "donkey\n").unwrap();
+
+        let handle =
+            Arc::new(Reloadable::<DictionaryStack>::load(vec![path.clone()], None).unwrap());
+        let before = handle.snapshot();
+        assert!(before.contains("donkey"));
+        assert!(!handle.reload_if_changed().unwrap());
+
+        fs::write(&path, "donkey\nmonkey\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        assert!(handle.reload_if_changed().unwrap());
+
+        assert!(!before.contains("monkey"));
+        assert!(handle.snapshot().contains("monkey"));
+        assert_eq!(handle.generation(), 1);
+
+        let current = handle.snapshot();
+        assert!(handle.update(|stack| stack.ignore("donkee")).is_none());
+        drop(current);
+        assert!(handle.update(|stack| stack.ignore("donkee")).is_some());
+        assert!(handle.snapshot().is_ignored("donkee"));
+        assert_eq!(handle.generation(), 1);
+
+        fs::write(&path, "roof\n").unwrap();
+        handle.reload_in_background().join().unwrap().unwrap();
+        assert!(handle.snapshot().contains("roof"));
+        assert!(!handle.snapshot().contains("donkey"));
+
+        fs::remove_file(&path).unwrap();
+        assert!(handle.reload().is_err());
+        assert!(handle.snapshot().contains("roof"));
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::{self, Write};
+
+use serde_json::{Value, json};
+
+use crate::spell_checker::Misspelling;
+
+const RULE_ID: &str = "spelling";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Jsonl,
+    Sarif,
+    Checkstyle,
+    Tsv,
+}
+
+pub struct FileReport {
+    pub file: String,
+    pub misspellings: Vec<Misspelling>,
+}
+
+pub fn write_report<W: Write>(
+    output: &mut W,
+    format: Format,
+    reports: &[FileReport],
+    max_suggestions: usize,
+) -> io::Result<()> {
+    let entries = reports.iter().flat_map(|report| {
+        report.misspellings.iter().map(move |misspelling| {
+            let suggestions = &misspelling.suggestions;
+            let suggestions = &suggestions[..suggestions.len().min(max_suggestions)];
+            (report.file.as_str(), misspelling, suggestions)
+        })
+    });
+
+    match format {
+        Format::Json => {
+            let entries: Vec<Value> = entries.map(|e| entry_json(e.0, e.1, e.2)).collect();
+            writeln!(output, "{}", Value::Array(entries))
+        }
+        Format::Jsonl => {
+            for (file, misspelling, suggestions) in entries {
+                writeln!(output, "{}", entry_json(file, misspelling, suggestions))?;
+            }
+            Ok(())
+        }
+        Format::Tsv => {
+            writeln!(output, "file\tline\tcolumn\tword\tsuggestions")?;
+            for (file, misspelling, suggestions) in entries {
+                writeln!(
+                    output,
+                    "{file}\t{}\t{}\t{}\t{}",
+                    misspelling.line,
+                    misspelling.column,
+                    misspelling.word,
+                    suggestions.join(",")
+                )?;
+            }
+            Ok(())
+        }
+        Format::Sarif => {
+            let results: Vec<Value> = entries.map(|e| sarif_result(e.0, e.1, e.2)).collect();
+            let sarif = json!({
+                "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                "version": "2.1.0",
+                "runs": [{
+                    "tool": {
+                        "driver": {
+                            "name": "lev-automaton",
+                            "version": env!("CARGO_PKG_VERSION"),
+                            "rules": [{
+                                "id": RULE_ID,
+                                "shortDescription": { "text": "Unknown word" },
+                            }],
+                        },
+                    },
+                    "columnKind": "unicodeCodePoints",
+                    "results": results,
+                }],
+            });
+            writeln!(output, "{sarif}")
+        }
+        Format::Checkstyle => {
+            writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+            writeln!(output, r#"<checkstyle version="4.3">"#)?;
+            for report in reports {
+                writeln!(output, r#"  <file name="{}">"#, xml_escape(&report.file))?;
+                for misspelling in &report.misspellings {
+                    let suggestions = &misspelling.suggestions;
+                    let suggestions = &suggestions[..suggestions.len().min(max_suggestions)];
+                    writeln!(
+                        output,
+                        r#"    <error line="{}" column="{}" severity="warning" message="{}" source="lev-automaton.{RULE_ID}"/>"#,
+                        misspelling.line,
+                        misspelling.column,
+                        xml_escape(&message(misspelling, suggestions)),
+                    )?;
+                }
+                writeln!(output, "  </file>")?;
+            }
+            writeln!(output, "</checkstyle>")
+        }
+    }
+}
+
+fn entry_json(file: &str, misspelling: &Misspelling, suggestions: &[String]) -> Value {
+    json!({
+        "file": file,
+        "line": misspelling.line,
+        "column": misspelling.column,
+        "word": misspelling.word,
+        "suggestions": suggestions,
+    })
+}
+
+fn sarif_result(file: &str, misspelling: &Misspelling, suggestions: &[String]) -> Value {
+    json!({
+        "ruleId": RULE_ID,
+        "level": "warning",
+        "message": { "text": message(misspelling, suggestions) },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": file },
+                "region": {
+                    "startLine": misspelling.line,
+                    "startColumn": misspelling.column,
+                    "endColumn": misspelling.column + misspelling.word.chars().count(),
+                },
+            },
+        }],
+        "properties": { "word": misspelling.word, "suggestions": suggestions },
+    })
+}
+
+fn message(misspelling: &Misspelling, suggestions: &[String]) -> String {
+    match suggestions {
+        [] => format!("Unknown word: {}", misspelling.word),
+        _ => format!(
+            "Unknown word: {} (did you mean {}?)",
+            misspelling.word,
+            suggestions.join(", ")
+        ),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::{
+        report::{FileReport, Format, write_report},
+        spell_checker::{EscalationPolicy, SpellChecker},
+        trie::Trie,
+    };
+
+    fn render(format: Format, reports: &[FileReport]) -> String {
+        let mut output = vec![];
+        write_report(&mut output, format, reports, 2).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        let policy = EscalationPolicy::default();
+        let spell_checker = SpellChecker::new(
+            Trie::new(&mut ["a", "donkey", "monkey", "on", "the", "roof"]),
+            |word, trie| policy.correct(word, trie),
+        );
+        let reports = [FileReport {
+            file: "notes.txt".to_string(),
+            misspellings: spell_checker.check_text("the roof\nÁ ronkey on the roof"),
+        }];
+
+        let json: Value = serde_json::from_str(&render(Format::Json, &reports)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[1]["file"], "notes.txt");
+        assert_eq!(json[1]["line"], 2);
+        assert_eq!(json[1]["column"], 3);
+        assert_eq!(json[1]["word"], "ronkey");
+        assert_eq!(
+            json[1]["suggestions"],
+            serde_json::json!(["donkey", "monkey"])
+        );
+
+        let jsonl = render(Format::Jsonl, &reports);
+        let lines: Vec<Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, json.as_array().unwrap().clone());
+
+        let tsv = render(Format::Tsv, &reports);
+        assert_eq!(
+            tsv.lines().nth(2),
+            Some("notes.txt\t2\t3\tronkey\tdonkey,monkey")
+        );
+
+        let sarif: Value = serde_json::from_str(&render(Format::Sarif, &reports)).unwrap();
+        let result = &sarif["runs"][0]["results"][1];
+        assert_eq!(result["ruleId"], "spelling");
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startColumn"], 3);
+        assert_eq!(region["endColumn"], 9);
+
+        let checkstyle = render(Format::Checkstyle, &reports);
+        assert!(checkstyle.contains(r#"<file name="notes.txt">"#));
+        assert!(checkstyle.contains(
+            r#"<error line="2" column="3" severity="warning" message="Unknown word: ronkey (did you mean donkey, monkey?)""#
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+use crate::trie::Trie;
+
+#[derive(Debug, Clone)]
+pub struct Segmenter {
+    pub max_parts: usize,
+    pub min_part_len: usize,
+    pub max_splits: usize,
+    pub join_valid_words: bool,
+}
+
+impl Default for Segmenter {
+    fn default() -> Self {
+        Self {
+            max_parts: 3,
+            min_part_len: 1,
+            max_splits: 3,
+            join_valid_words: false,
+        }
+    }
+}
+
+impl Segmenter {
+    pub fn split(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        let mut splits = vec![];
+        self.collect_splits(word, tries, &mut vec![], &mut splits);
+
+        splits.sort_by_key(|parts: &Vec<&str>| {
+            let shortest_part = parts.iter().map(|p| p.chars().count()).min();
+            (parts.len(), std::cmp::Reverse(shortest_part))
+        });
+        splits
+            .into_iter()
+            .take(self.max_splits)
+            .map(|parts| parts.join(" "))
+            .collect()
+    }
+
+    fn collect_splits<'a>(
+        &self,
+        rest: &'a str,
+        tries: &[&Trie],
+        parts: &mut Vec<&'a str>,
+        splits: &mut Vec<Vec<&'a str>>,
+    ) {
+        if parts.len() == self.max_parts {
+            return;
+        }
+
+        let mut prefixes: Vec<usize> = tries.iter().flat_map(|t| t.prefixes(rest)).collect();
+        prefixes.sort_unstable();
+        prefixes.dedup();
+
+        for prefix_len in prefixes {
+            let (part, remainder) = rest.split_at(prefix_len);
+            if part.chars().count() < self.min_part_len {
+                continue;
+            }
+
+            parts.push(part);
+            if remainder.is_empty() {
+                if parts.len() > 1 {
+                    splits.push(parts.clone());
+                }
+            } else {
+                self.collect_splits(remainder, tries, parts, splits);
+            }
+            parts.pop();
+        }
+    }
+
+    pub fn join(&self, first: &str, second: &str, trie: &Trie) -> Option<String> {
+        let joined = format!("{first}{second}");
+        trie.contains(&joined).then_some(joined)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{segmentation::Segmenter, trie::Trie};
+
+    #[test]
+    fn test_split() {
+        let mut words = vec!["a", "in", "the", "lot", "to", "get", "her", "together"];
+        let trie = &[&Trie::new(&mut words)];
+        let segmenter = Segmenter::default();
+
+        assert_eq!(segmenter.split("inthe", trie), vec!["in the"]);
+        assert_eq!(segmenter.split("alot", trie), vec!["a lot"]);
+        assert_eq!(segmenter.split("togetherthe", trie), vec!["together the"]);
+        assert!(
+            segmenter
+                .split("inthelot", trie)
+                .contains(&"in the lot".to_string())
+        );
+        assert!(segmenter.split("atogethertheto", trie).is_empty());
+
+        let strict = Segmenter {
+            min_part_len: 2,
+            ..Segmenter::default()
+        };
+        assert!(strict.split("alot", trie).is_empty());
+    }
+
+    #[test]
+    fn test_join() {
+        let mut words = vec!["some", "thing", "something"];
+        let trie = Trie::new(&mut words);
+        let segmenter = Segmenter::default();
+
+        assert_eq!(
+            segmenter.join("some", "thing", &trie),
+            Some("something".to_string())
+        );
+        assert_eq!(segmenter.join("thing", "some", &trie), None);
+    }
+}
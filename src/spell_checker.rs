@@ -1,29 +1,955 @@
-use crate::trie::Trie;
+use std::{
+    collections::{BTreeSet, HashSet},
+    io,
+    ops::Range,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
 
-pub struct SpellChecker<F>
+use crate::{
+    automaton::Deterministic,
+    cache::{CacheStats, LruCache},
+    compound::{CompoundConfig, Compounds},
+    dictionary::DictionaryStack,
+    filter::Filter,
+    levenshtein_automaton::{self, EditCosts, LevenshteinAutomaton},
+    matcher::Prefix,
+    phonetic::PhoneticIndex,
+    segmentation::Segmenter,
+    tokenizer::{Token, Tokenizer},
+    trie::Trie,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Misspelling {
+    pub byte_range: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+pub trait Corrector {
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String>;
+
+    fn score(&self, word: &str, candidate: &str) -> f64 {
+        levenshtein_automaton::distance(word, candidate) as f64
+    }
+}
+
+impl<F> Corrector for F
 where
     F: Fn(&str, &Trie) -> Vec<String>,
 {
-    trie: Trie,
-    correction_func: F,
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        tries.iter().flat_map(|trie| self(word, trie)).collect()
+    }
+}
+
+pub struct SpellChecker<F>
+where
+    F: Corrector,
+{
+    dictionaries: DictionaryStack,
+    corrector: F,
+    segmenter: Option<Segmenter>,
+    compounds: Option<Compounds>,
+    phonetic: Option<PhoneticIndex>,
+    split_identifiers: bool,
+    cache: Option<Mutex<LruCache<String, Vec<String>>>>,
 }
 
 impl<F> SpellChecker<F>
 where
     F: Fn(&str, &Trie) -> Vec<String>,
 {
-    pub fn new(trie: Trie, correction_func: F) -> Self {
+    pub fn new(dictionaries: impl Into<DictionaryStack>, correction_func: F) -> Self {
+        Self::from_corrector(dictionaries, correction_func)
+    }
+}
+
+impl<F> SpellChecker<F>
+where
+    F: Corrector,
+{
+    pub fn from_corrector(dictionaries: impl Into<DictionaryStack>, corrector: F) -> Self {
         Self {
-            trie,
-            correction_func,
+            dictionaries: dictionaries.into(),
+            corrector,
+            segmenter: None,
+            compounds: None,
+            phonetic: None,
+            split_identifiers: false,
+            cache: None,
         }
     }
 
+    pub fn with_segmenter(mut self, segmenter: Segmenter) -> Self {
+        self.assert_uncached();
+        self.segmenter = Some(segmenter);
+        self
+    }
+
+    pub fn with_compounds(mut self, config: &CompoundConfig) -> Self {
+        self.assert_uncached();
+        let words: BTreeSet<String> = self
+            .dictionaries
+            .tries()
+            .flat_map(|trie| trie.filter(&Prefix("")))
+            .collect();
+        let trie = Trie::new(&mut words.iter().map(String::as_str).collect::<Vec<_>>());
+        self.compounds = Some(Compounds::new(&trie, config));
+        self
+    }
+
+    pub fn with_phonetic_index(mut self, index: PhoneticIndex) -> Self {
+        self.assert_uncached();
+        self.phonetic = Some(index);
+        self
+    }
+
+    pub fn with_phonetic(self) -> Self {
+        let index = PhoneticIndex::from_words(
+            self.dictionaries
+                .tries()
+                .flat_map(|trie| trie.filter(&Prefix(""))),
+        );
+        self.with_phonetic_index(index)
+    }
+
+    pub fn with_identifier_splitting(mut self, enabled: bool) -> Self {
+        self.assert_uncached();
+        self.split_identifiers = enabled;
+        self
+    }
+
+    // Suggestions are cached by word alone, so every option has to be set
+    // before the cache is enabled.
+    fn assert_uncached(&self) {
+        assert!(
+            self.cache.is_none(),
+            "spell checker options must be set before with_cache"
+        );
+    }
+
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(Mutex::new(LruCache::new(capacity)));
+        self
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache
+            .as_ref()
+            .map(|cache| cache.lock().unwrap_or_else(|e| e.into_inner()).stats())
+    }
+
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+    }
+
+    pub fn dictionaries(&self) -> &DictionaryStack {
+        &self.dictionaries
+    }
+
+    pub fn dictionaries_mut(&mut self) -> &mut DictionaryStack {
+        self.clear_cache();
+        &mut self.dictionaries
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.dictionaries.contains(word)
+            || self
+                .compounds
+                .as_ref()
+                .is_some_and(|compounds| compounds.recognizes(word))
+    }
+
     pub fn check_word(&self, word: &str) -> Result<(), Vec<String>> {
-        if self.trie.contains(word) {
-            Ok(())
-        } else {
-            Err((self.correction_func)(word, &self.trie))
+        if self.contains(word) {
+            return Ok(());
+        }
+
+        let Some(cache) = &self.cache else {
+            return Err(self.suggest(word));
+        };
+        let cached = cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&word.to_string());
+        if let Some(suggestions) = cached {
+            return Err(suggestions);
+        }
+        let suggestions = self.suggest(word);
+        cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(word.to_string(), suggestions.clone());
+        Err(suggestions)
+    }
+
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let tries: Vec<&Trie> = self.dictionaries.tries().collect();
+        let mut candidates = self.corrector.candidates(word, &tries);
+        if let Some(compounds) = &self.compounds {
+            candidates.extend(compounds.fuzzy(word));
+        }
+        if let Some(segmenter) = &self.segmenter {
+            candidates.extend(segmenter.split(word, &tries));
+        }
+        if let Some(phonetic) = &self.phonetic {
+            candidates.extend(phonetic.lookup(word));
+        }
+
+        let mut seen = HashSet::new();
+        let mut suggestions: Vec<(f64, String)> = candidates
+            .into_iter()
+            .filter(|s| !self.dictionaries.is_ignored(s) && seen.insert(s.clone()))
+            .map(|s| (self.corrector.score(word, &s), s))
+            .collect();
+        suggestions.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        suggestions.into_iter().map(|(_, s)| s).collect()
+    }
+
+    pub fn tokenizer<'a>(&self, text: &'a str) -> Tokenizer<'a> {
+        Tokenizer::new(text).with_identifier_splitting(self.split_identifiers)
+    }
+
+    pub fn check_tokens<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> impl Iterator<Item = (Token<'a>, Result<(), Vec<String>>)> + 'a {
+        self.tokenizer(text).map(|token| {
+            let lowercase = token.text.to_lowercase();
+            let res = if self.contains(&lowercase) || self.contains(token.text) {
+                Ok(())
+            } else {
+                self.check_word(&lowercase).map_err(|suggestions| {
+                    let mut seen = HashSet::new();
+                    suggestions
+                        .iter()
+                        .map(|suggestion| match_case(token.text, suggestion))
+                        .filter(|suggestion| seen.insert(suggestion.clone()))
+                        .collect()
+                })
+            };
+            (token, res)
+        })
+    }
+
+    pub fn check_text(&self, text: &str) -> Vec<Misspelling> {
+        self.check_masked(text, text)
+    }
+
+    pub fn check_filtered(&self, text: &str, filter: Filter) -> io::Result<Vec<Misspelling>> {
+        match filter {
+            Filter::Plain => Ok(self.check_text(text)),
+            filter => Ok(self.check_masked(text, &filter.mask(text)?)),
+        }
+    }
+
+    fn check_masked(&self, text: &str, masked: &str) -> Vec<Misspelling> {
+        let tokens: Vec<_> = self.check_tokens(masked).collect();
+        let mut line_index = LineIndex::default();
+        let mut misspellings = vec![];
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let (token, res) = &tokens[i];
+
+            if let Some(((next, next_res), segmenter)) =
+                tokens.get(i + 1).zip(self.segmenter.as_ref())
+                && (res.is_err() || next_res.is_err() || segmenter.join_valid_words)
+                && token.byte_range.end < next.byte_range.start
+                && text[token.byte_range.end..next.byte_range.start]
+                    .chars()
+                    .all(char::is_whitespace)
+                && let Some(joined) = self
+                    .dictionaries
+                    .tries()
+                    .find_map(|trie| segmenter.join(token.text, next.text, trie))
+            {
+                let byte_range = token.byte_range.start..next.byte_range.end;
+                let (line, column) = line_index.position(text, byte_range.start);
+                misspellings.push(Misspelling {
+                    line,
+                    column,
+                    word: text[byte_range.clone()].to_string(),
+                    byte_range,
+                    suggestions: vec![joined],
+                });
+                i += 2;
+                continue;
+            }
+
+            if let Err(suggestions) = res {
+                let (line, column) = line_index.position(text, token.byte_range.start);
+                misspellings.push(Misspelling {
+                    line,
+                    column,
+                    byte_range: token.byte_range.clone(),
+                    word: token.text.to_string(),
+                    suggestions: suggestions.clone(),
+                });
+            }
+            i += 1;
+        }
+
+        misspellings
+    }
+
+    pub fn correct_identifier(&self, identifier: &str) -> Option<String> {
+        let mut corrected = String::with_capacity(identifier.len());
+        let mut copied_until = 0;
+
+        for token in Tokenizer::new(identifier).with_identifier_splitting(true) {
+            let lowercase = token.text.to_lowercase();
+            if self.contains(&lowercase) {
+                continue;
+            }
+            let suggestions = self.check_word(&lowercase).err().unwrap_or_default();
+            if let Some(suggestion) = suggestions.iter().find(|s| !s.contains(' ')) {
+                corrected.push_str(&identifier[copied_until..token.byte_range.start]);
+                corrected.push_str(&match_case(token.text, suggestion));
+                copied_until = token.byte_range.end;
+            }
+        }
+
+        (copied_until > 0).then(|| corrected + &identifier[copied_until..])
+    }
+
+    pub fn autocorrect(
+        &self,
+        text: &str,
+        filter: Filter,
+        min_confidence: Option<f64>,
+    ) -> io::Result<(String, Vec<Correction>)> {
+        let mut corrected = String::with_capacity(text.len());
+        let mut corrections = vec![];
+        let mut copied_until = 0;
+
+        for misspelling in self.check_filtered(text, filter)? {
+            let suggestions = &misspelling.suggestions;
+            let confident = match suggestions.as_slice() {
+                [_] => true,
+                [_, ..] => min_confidence.is_some_and(|threshold| {
+                    confidence(&misspelling.word, suggestions) >= threshold
+                }),
+                [] => false,
+            };
+            if !confident {
+                continue;
+            }
+
+            let range = misspelling.byte_range;
+            corrected.push_str(&text[copied_until..range.start]);
+            let replacement = match_case(&misspelling.word, &suggestions[0]);
+            corrected.push_str(&replacement);
+            copied_until = range.end;
+
+            corrections.push(Correction {
+                byte_range: range,
+                line: misspelling.line,
+                column: misspelling.column,
+                original: misspelling.word,
+                replacement,
+            });
+        }
+        corrected.push_str(&text[copied_until..]);
+
+        Ok((corrected, corrections))
+    }
+}
+
+impl<F> SpellChecker<F>
+where
+    F: Corrector + Sync,
+{
+    pub fn check_many<S>(&self, texts: &[S]) -> Vec<Vec<Misspelling>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        parallel_map(texts, |text| self.check_text(text.as_ref()))
+    }
+
+    pub fn suggest_many<S>(&self, words: &[S]) -> Vec<Result<(), Vec<String>>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        parallel_map(words, |word| self.check_word(word.as_ref()))
+    }
+}
+
+fn parallel_map<T, R>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(items.len());
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(idx) else {
+                            break;
+                        };
+                        results.push((idx, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    });
+
+    results.sort_unstable_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    pub byte_range: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    pub original: String,
+    pub replacement: String,
+}
+
+pub fn confidence(word: &str, suggestions: &[String]) -> f64 {
+    match suggestions {
+        [] => 0.0,
+        [_] => 1.0,
+        [first, second, ..] => {
+            let first = levenshtein_automaton::distance(word, first);
+            let second = levenshtein_automaton::distance(word, second);
+            match second {
+                0 => 0.0,
+                _ => second.saturating_sub(first) as f64 / second as f64,
+            }
         }
     }
 }
+
+pub fn match_case(original: &str, replacement: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return replacement.to_string();
+    };
+    let rest: Vec<_> = letters.collect();
+
+    if first.is_uppercase() && !rest.is_empty() && rest.iter().all(|c| c.is_uppercase()) {
+        replacement.to_uppercase()
+    } else if first.is_uppercase() {
+        let mut chars = replacement.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        replacement.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LineIndex {
+    line: usize,
+    line_start: usize,
+    scanned_until: usize,
+}
+
+impl LineIndex {
+    fn position(&mut self, text: &str, byte_offset: usize) -> (usize, usize) {
+        for (idx, _) in text[self.scanned_until..byte_offset].match_indices('\n') {
+            self.line += 1;
+            self.line_start = self.scanned_until + idx + 1;
+        }
+        self.scanned_until = byte_offset;
+
+        (
+            self.line + 1,
+            text[self.line_start..byte_offset].chars().count() + 1,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EscalationPolicy {
+    pub start_degree: usize,
+    pub max_degree: usize,
+    pub min_candidates: usize,
+    pub length_limits: Vec<(usize, usize)>,
+    pub costs: EditCosts,
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        Self {
+            start_degree: 1,
+            max_degree: 3,
+            min_candidates: 1,
+            length_limits: vec![(0, 1), (5, 2), (9, 3)],
+            costs: EditCosts::default(),
+        }
+    }
+}
+
+impl EscalationPolicy {
+    pub fn max_degree_for(&self, word: &str) -> usize {
+        let word_len = word.chars().count();
+        self.length_limits
+            .iter()
+            .filter(|(min_len, _)| word_len >= *min_len)
+            .map(|(_, degree)| *degree)
+            .max()
+            .unwrap_or(self.start_degree)
+            .min(self.max_degree)
+    }
+
+    pub fn correct(&self, word: &str, trie: &Trie) -> Vec<String> {
+        self.candidates(word, &[trie])
+    }
+}
+
+impl Corrector for EscalationPolicy {
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        let max_degree = self.max_degree_for(word).max(self.start_degree);
+        let mut candidates = vec![];
+
+        for degree in self.start_degree..=max_degree {
+            let aut: LevenshteinAutomaton<Deterministic> =
+                LevenshteinAutomaton::with_costs(word, degree * self.costs.edit, &self.costs)
+                    .into();
+            candidates = tries
+                .iter()
+                .flat_map(|trie| trie.filter(aut.get_automaton()))
+                .collect();
+
+            if candidates.len() >= self.min_candidates {
+                break;
+            }
+        }
+
+        candidates.sort_by_cached_key(|s| (self.costs.distance(word, s), s.clone()));
+        candidates.dedup();
+        candidates
+    }
+
+    fn score(&self, word: &str, candidate: &str) -> f64 {
+        self.costs.distance(word, candidate) as f64
+    }
+}
+
+pub fn rank_suggestions(word: &str, suggestions: &mut Vec<String>) {
+    suggestions.sort_by_cached_key(|s| (levenshtein_automaton::distance(word, s), s.clone()));
+    suggestions.dedup();
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        automaton::Deterministic,
+        compound::CompoundConfig,
+        dictionary::DictionaryStack,
+        filter::Filter,
+        keyboard::Layout,
+        levenshtein_automaton::LevenshteinAutomaton,
+        segmentation::Segmenter,
+        spell_checker::{EscalationPolicy, SpellChecker, confidence, match_case, rank_suggestions},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_check_text() {
+        let mut words = vec![
+            "a", "donkey", "on", "the", "roof", "monkey", "climbs", "chair",
+        ];
+        let spell_checker = SpellChecker::new(Trie::new(&mut words), |word, trie| {
+            let aut: LevenshteinAutomaton<Deterministic> =
+                LevenshteinAutomaton::new(word, 2).into();
+            let mut suggestions = trie.filter(aut.get_automaton());
+            rank_suggestions(word, &mut suggestions);
+            suggestions
+        });
+
+        let text = "The donkep on the roof,\n  monkei climbs the chaor.";
+        let misspellings = spell_checker.check_text(text);
+
+        assert_eq!(misspellings.len(), 3);
+
+        assert_eq!(misspellings[0].word, "donkep");
+        assert_eq!(misspellings[0].byte_range, 4..10);
+        assert_eq!((misspellings[0].line, misspellings[0].column), (1, 5));
+        assert_eq!(misspellings[0].suggestions, vec!["donkey", "monkey"]);
+
+        assert_eq!(misspellings[1].word, "monkei");
+        assert_eq!((misspellings[1].line, misspellings[1].column), (2, 3));
+        assert_eq!(misspellings[1].suggestions, vec!["monkey", "donkey"]);
+
+        assert_eq!(misspellings[2].word, "chaor");
+        assert_eq!(&text[misspellings[2].byte_range.clone()], "chaor");
+        assert_eq!((misspellings[2].line, misspellings[2].column), (2, 21));
+        assert_eq!(misspellings[2].suggestions, vec!["chair"]);
+    }
+
+    #[test]
+    fn test_escalation_policy() {
+        let mut words = vec!["cat", "cart", "carrot", "parrot", "station", "starting"];
+        let trie = Trie::new(&mut words);
+        let policy = EscalationPolicy::default();
+
+        assert_eq!(policy.max_degree_for("cst"), 1);
+        assert_eq!(policy.max_degree_for("carot"), 2);
+        assert_eq!(policy.max_degree_for("stationed"), 3);
+
+        let mut corrections = policy.correct("cst", &trie);
+        corrections.sort();
+        assert_eq!(corrections, vec!["cat"]);
+
+        assert!(policy.correct("dgo", &trie).is_empty());
+
+        let mut corrections = policy.correct("carrt", &trie);
+        corrections.sort();
+        assert_eq!(corrections, vec!["carrot", "cart"]);
+
+        assert_eq!(policy.correct("sttaion", &trie), vec!["station"]);
+
+        let strict = EscalationPolicy {
+            min_candidates: 2,
+            ..EscalationPolicy::default()
+        };
+        assert_eq!(policy.correct("parot", &trie), vec!["parrot"]);
+        let mut corrections = strict.correct("parot", &trie);
+        corrections.sort();
+        assert_eq!(corrections, vec!["carrot", "cart", "parrot"]);
+    }
+
+    #[test]
+    fn test_segmentation_suggestions() {
+        let mut words = vec![
+            "a",
+            "in",
+            "the",
+            "lot",
+            "slot",
+            "some",
+            "thing",
+            "something",
+        ];
+        let policy = EscalationPolicy::default();
+        let spell_checker = SpellChecker::new(Trie::new(&mut words), |word, trie| {
+            policy.correct(word, trie)
+        })
+        .with_segmenter(Segmenter::default());
+
+        assert_eq!(
+            spell_checker.check_word("inthe"),
+            Err(vec!["in the".into(), "the".into()])
+        );
+        assert_eq!(
+            spell_checker.check_word("alot"),
+            Err(vec!["lot".into(), "slot".into(), "a lot".into()])
+        );
+
+        let keyboard = SpellChecker::from_corrector(
+            Trie::new(&mut words),
+            EscalationPolicy {
+                costs: Layout::Qwerty.edit_costs(),
+                ..EscalationPolicy::default()
+            },
+        )
+        .with_segmenter(Segmenter::default());
+        assert_eq!(
+            keyboard.check_word("alot"),
+            Err(vec!["slot".into(), "lot".into(), "a lot".into()])
+        );
+        assert_eq!(
+            keyboard.check_word("inthe"),
+            Err(vec!["in the".into(), "the".into()])
+        );
+
+        let text = "the somet hing, some thing";
+        let misspellings = spell_checker.check_text(text);
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].word, "somet hing");
+        assert_eq!(misspellings[0].byte_range, 4..14);
+        assert_eq!(misspellings[0].suggestions, vec!["something"]);
+
+        let spell_checker = spell_checker.with_segmenter(Segmenter {
+            join_valid_words: true,
+            ..Segmenter::default()
+        });
+        let misspellings = spell_checker.check_text(text);
+        assert_eq!(misspellings.len(), 2);
+        assert_eq!(misspellings[1].word, "some thing");
+        assert_eq!((misspellings[1].line, misspellings[1].column), (1, 17));
+    }
+
+    #[test]
+    fn test_compound_mode() {
+        let mut words = vec!["haus", "tür", "schlüssel", "arbeit", "amt"];
+        let policy = EscalationPolicy::default();
+        let spell_checker = SpellChecker::new(Trie::new(&mut words), |word, trie| {
+            policy.correct(word, trie)
+        })
+        .with_compounds(&CompoundConfig::german());
+
+        assert_eq!(spell_checker.check_word("haustürschlüssel"), Ok(()));
+        assert_eq!(
+            spell_checker.check_word("Arbeitsamt"),
+            Err(vec!["arbeitsamt".into()])
+        );
+        assert_eq!(
+            spell_checker.check_word("haustüt"),
+            Err(vec!["haustür".into()])
+        );
+
+        let misspellings = spell_checker.check_text("Arbeitsamt, haustüt");
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].word, "haustüt");
+
+        let dictionaries =
+            DictionaryStack::new(Trie::new(&mut words)).with_layer(Trie::new(&mut ["tokio"]));
+        let spell_checker =
+            SpellChecker::new(dictionaries, |word, trie| policy.correct(word, trie))
+                .with_compounds(&CompoundConfig::german());
+        assert_eq!(spell_checker.check_word("tokioamt"), Ok(()));
+        assert_eq!(
+            spell_checker.check_word("tokioant"),
+            Err(vec!["tokioamt".into()])
+        );
+    }
+
+    #[test]
+    fn test_dictionary_stack() {
+        let dictionaries = DictionaryStack::new(Trie::new(&mut ["the", "roof", "roofs"]))
+            .with_layer(Trie::new(&mut ["rooftop"]))
+            .with_ignored(["roofs", "teh"]);
+        let policy = EscalationPolicy::default();
+        let mut spell_checker =
+            SpellChecker::new(dictionaries, |word, trie| policy.correct(word, trie));
+
+        assert_eq!(spell_checker.check_word("teh"), Ok(()));
+        assert_eq!(
+            spell_checker.check_word("rooftp"),
+            Err(vec!["rooftop".into(), "roof".into()])
+        );
+        assert_eq!(spell_checker.check_word("roofz"), Err(vec!["roof".into()]));
+
+        spell_checker.dictionaries_mut().ignore("roofz");
+        assert!(spell_checker.check_text("The roofz").is_empty());
+
+        let spell_checker =
+            SpellChecker::from_corrector(spell_checker.dictionaries().clone(), policy);
+        assert_eq!(
+            spell_checker.check_word("rooftp"),
+            Err(vec!["rooftop".into()])
+        );
+        assert_eq!(spell_checker.check_word("roofx"), Err(vec!["roof".into()]));
+    }
+
+    #[test]
+    fn test_autocorrect() {
+        let mut words = vec!["a", "donkey", "on", "the", "roof", "monkey", "climbs"];
+        let spell_checker = SpellChecker::new(Trie::new(&mut words), |word, trie| {
+            let aut: LevenshteinAutomaton<Deterministic> =
+                LevenshteinAutomaton::new(word, 2).into();
+            let mut suggestions = trie.filter(aut.get_automaton());
+            rank_suggestions(word, &mut suggestions);
+            suggestions
+        });
+        let text = "Teh donkep, on THE rooof!\n  Monkei climbs qwxz.";
+
+        let (corrected, corrections) = spell_checker
+            .autocorrect(text, Filter::Plain, None)
+            .unwrap();
+        assert_eq!(corrected, "The donkep, on THE roof!\n  Monkei climbs qwxz.");
+        assert_eq!(corrections.len(), 2);
+        assert_eq!(corrections[0].original, "Teh");
+        assert_eq!(corrections[0].replacement, "The");
+        assert_eq!(corrections[1].byte_range, 19..24);
+
+        let (corrected, corrections) = spell_checker
+            .autocorrect(text, Filter::Plain, Some(0.5))
+            .unwrap();
+        assert_eq!(corrected, "The donkey, on THE roof!\n  Monkey climbs qwxz.");
+        assert_eq!(corrections.len(), 4);
+        assert_eq!((corrections[3].line, corrections[3].column), (2, 3));
+
+        assert_eq!(
+            confidence("donkep", &["donkey".into(), "monkey".into()]),
+            0.5
+        );
+        assert_eq!(confidence("donkep", &["donkey".into()]), 1.0);
+        assert_eq!(match_case("TEH", "the"), "THE");
+        assert_eq!(match_case("Ónkey", "önkey"), "Önkey");
+        assert_eq!(match_case("A", "a"), "A");
+    }
+
+    #[test]
+    fn test_filtered_text() {
+        let policy = EscalationPolicy::default();
+        let spell_checker = SpellChecker::new(
+            Trie::new(&mut ["a", "donkey", "on", "the", "roof"]),
+            |word, trie| policy.correct(word, trie),
+        );
+
+        let text = "The `ÁÁÁ` donkep\n```\nqwxz\n```\non [the roof](https://roof.xyz/qwxz).";
+        assert_eq!(spell_checker.check_text(text).len(), 3);
+        let misspellings = spell_checker
+            .check_filtered(text, Filter::Markdown)
+            .unwrap();
+
+        assert_eq!(misspellings.len(), 1);
+        assert_eq!(misspellings[0].word, "donkep");
+        assert_eq!(&text[misspellings[0].byte_range.clone()], "donkep");
+        assert_eq!((misspellings[0].line, misspellings[0].column), (1, 11));
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let policy = EscalationPolicy::default();
+        let mut words = vec![
+            "receive", "message", "max", "retries", "count", "http", "server", "utf", "encoder",
+        ];
+        let spell_checker = SpellChecker::new(Trie::new(&mut words), |word, trie| {
+            policy.correct(word, trie)
+        })
+        .with_identifier_splitting(true);
+
+        let misspellings = spell_checker.check_text("recieveMessage(max_retrys_count)");
+        assert_eq!(misspellings.len(), 2);
+        assert_eq!(misspellings[0].word, "recieve");
+        assert_eq!(misspellings[1].word, "retrys");
+        assert_eq!(misspellings[1].byte_range, 19..25);
+
+        let correct = |identifier| spell_checker.correct_identifier(identifier);
+        assert_eq!(correct("recieveMessage"), Some("receiveMessage".into()));
+        assert_eq!(
+            correct("max_retrys_count"),
+            Some("max_retries_count".into())
+        );
+        assert_eq!(correct("MAX_RETRYS"), Some("MAX_RETRIES".into()));
+        assert_eq!(correct("HTTPSerever"), Some("HTTPServer".into()));
+        assert_eq!(correct("utf8-encodr"), Some("utf8-encoder".into()));
+        assert_eq!(correct("receiveMessage"), None);
+    }
+
+    #[test]
+    fn test_batch() {
+        let policy = EscalationPolicy::default();
+        let spell_checker = SpellChecker::new(
+            Trie::new(&mut ["a", "donkey", "monkey", "on", "the", "roof"]),
+            |word, trie| policy.correct(word, trie),
+        );
+
+        let words: Vec<_> = (0..200)
+            .map(|i| ["donkep", "roof", "rooof", "qwxz"][i % 4])
+            .collect();
+        let results = spell_checker.suggest_many(&words);
+        assert_eq!(results.len(), words.len());
+        for (word, result) in words.iter().zip(&results) {
+            assert_eq!(*result, spell_checker.check_word(word));
+        }
+
+        let texts = ["a donkep", "the roof", "monkei on the rof"];
+        let misspellings = spell_checker.check_many(&texts);
+        assert_eq!(
+            misspellings.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![1, 0, 2]
+        );
+        assert_eq!(misspellings[2][1].word, "rof");
+        assert!(spell_checker.check_many::<&str>(&[]).is_empty());
+
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        assert_send_sync(&spell_checker);
+    }
+
+    #[test]
+    fn test_cache() {
+        let calls = AtomicUsize::new(0);
+        let policy = EscalationPolicy::default();
+        let mut spell_checker = SpellChecker::new(
+            Trie::new(&mut ["a", "donkey", "monkey", "on", "the", "roof"]),
+            |word, trie| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                policy.correct(word, trie)
+            },
+        )
+        .with_cache(2);
+
+        let text = "thw donkei on thw roof, thw donkei";
+        let misspellings = spell_checker.check_text(text);
+        assert_eq!(misspellings.len(), 5);
+        assert_eq!(misspellings[0].suggestions, vec!["the"]);
+        assert_eq!(misspellings[2].suggestions, vec!["the"]);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        let stats = spell_checker.cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.len), (3, 2, 2));
+
+        let misspellings = spell_checker.check_text("Thw THW thw");
+        let suggestions: Vec<_> = misspellings.iter().map(|m| &m.suggestions).collect();
+        assert_eq!(suggestions, [&["The"], &["THE"], &["the"]]);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        spell_checker.dictionaries_mut().ignore("the");
+        assert_eq!(spell_checker.check_word("thw"), Err(vec![]));
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "before with_cache")]
+    fn test_options_after_cache() {
+        let policy = EscalationPolicy::default();
+        let _ = SpellChecker::new(Trie::new(&mut ["the"]), |word, trie| {
+            policy.correct(word, trie)
+        })
+        .with_cache(2)
+        .with_phonetic();
+    }
+
+    #[test]
+    fn test_phonetic_candidates() {
+        let policy = EscalationPolicy::default();
+        let words = || Trie::new(&mut ["a", "phone", "tough", "on", "the", "roof"]);
+
+        let plain = SpellChecker::new(words(), |word, trie| policy.correct(word, trie));
+        assert_eq!(plain.check_word("tuff"), Err(vec![]));
+
+        let phonetic =
+            SpellChecker::new(words(), |word, trie| policy.correct(word, trie)).with_phonetic();
+        assert_eq!(phonetic.check_word("tuff"), Err(vec!["tough".into()]));
+        assert_eq!(phonetic.check_word("fone"), Err(vec!["phone".into()]));
+        assert_eq!(phonetic.check_word("tough"), Ok(()));
+
+        let dictionaries =
+            DictionaryStack::new(words()).with_layer(Trie::new(&mut ["knowledge", "psychology"]));
+        let phonetic = SpellChecker::new(dictionaries, |word, trie| policy.correct(word, trie))
+            .with_phonetic();
+        assert_eq!(phonetic.check_word("nollij"), Err(vec!["knowledge".into()]));
+        assert_eq!(
+            phonetic.check_word("sikology"),
+            Err(vec!["psychology".into()])
+        );
+    }
+}
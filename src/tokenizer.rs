@@ -0,0 +1,234 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub byte_range: Range<usize>,
+    pub char_range: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    text: &'a str,
+    byte_offset: usize,
+    char_offset: usize,
+    pending: Vec<Token<'a>>,
+    split_identifiers: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            byte_offset: 0,
+            char_offset: 0,
+            pending: vec![],
+            split_identifiers: false,
+        }
+    }
+
+    pub fn with_identifier_splitting(mut self, enabled: bool) -> Self {
+        self.split_identifiers = enabled;
+        self
+    }
+
+    fn next_chunk(&mut self) -> Option<(&'a str, usize, usize)> {
+        let rest = &self.text[self.byte_offset..];
+        let start = rest.find(|c: char| !c.is_whitespace())?;
+        self.char_offset += rest[..start].chars().count();
+        let rest = &rest[start..];
+        let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+        let chunk = &rest[..len];
+        let chunk_byte_offset = self.byte_offset + start;
+        let chunk_char_offset = self.char_offset;
+
+        self.byte_offset = chunk_byte_offset + len;
+        self.char_offset += chunk.chars().count();
+
+        Some((chunk, chunk_byte_offset, chunk_char_offset))
+    }
+
+    fn split_chunk(
+        &self,
+        chunk: &'a str,
+        byte_offset: usize,
+        char_offset: usize,
+    ) -> Vec<Token<'a>> {
+        if is_url(chunk) || is_email(chunk) {
+            return vec![];
+        }
+
+        let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+        let mut tokens = vec![];
+        let mut i = 0;
+
+        while i < chars.len() {
+            if !chars[i].1.is_alphanumeric() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < chars.len() {
+                let c = chars[i].1;
+                let is_inner_apostrophe = is_apostrophe(c)
+                    && chars
+                        .get(i + 1)
+                        .is_some_and(|(_, next)| next.is_alphabetic());
+                if c.is_alphanumeric() || (i > start && is_inner_apostrophe) {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+
+            for part in word_parts(&chars[start..i], self.split_identifiers) {
+                let (start, end) = (start + part.start, start + part.end);
+                let byte_start = chars[start].0;
+                let byte_end = chars.get(end).map_or(chunk.len(), |(idx, _)| *idx);
+                let text = &chunk[byte_start..byte_end];
+
+                if !text.chars().any(|c| c.is_numeric()) {
+                    tokens.push(Token {
+                        text,
+                        byte_range: byte_offset + byte_start..byte_offset + byte_end,
+                        char_range: char_offset + start..char_offset + end,
+                    });
+                }
+            }
+        }
+
+        tokens
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let (chunk, byte_offset, char_offset) = self.next_chunk()?;
+            self.pending = self.split_chunk(chunk, byte_offset, char_offset);
+            self.pending.reverse();
+        }
+
+        self.pending.pop()
+    }
+}
+
+fn word_parts(chars: &[(usize, char)], split_identifiers: bool) -> Vec<Range<usize>> {
+    let mut parts = vec![];
+    let mut start = 0;
+
+    if split_identifiers {
+        for i in 1..chars.len() {
+            let (prev, c) = (chars[i - 1].1, chars[i].1);
+            let next = chars.get(i + 1).map(|(_, next)| *next);
+
+            let is_boundary = (prev.is_lowercase() && c.is_uppercase())
+                || (prev.is_uppercase()
+                    && c.is_uppercase()
+                    && next.is_some_and(char::is_lowercase))
+                || (prev.is_numeric() != c.is_numeric()
+                    && !is_apostrophe(prev)
+                    && !is_apostrophe(c));
+            if is_boundary {
+                parts.push(start..i);
+                start = i;
+            }
+        }
+    }
+    parts.push(start..chars.len());
+
+    parts
+}
+
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '\u{2019}')
+}
+
+fn is_url(chunk: &str) -> bool {
+    chunk.contains("://") || chunk.starts_with("www.")
+}
+
+fn is_email(chunk: &str) -> bool {
+    match chunk.split_once('@') {
+        Some((user, domain)) => !user.is_empty() && domain.contains('.'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tokenizer::Tokenizer;
+
+    fn words(text: &str) -> Vec<&str> {
+        Tokenizer::new(text).map(|t| t.text).collect()
+    }
+
+    #[test]
+    fn test_strips_punctuation() {
+        assert_eq!(
+            words("a donkey on the roof, as expeted."),
+            vec!["a", "donkey", "on", "the", "roof", "as", "expeted"]
+        );
+        assert_eq!(words("(\"quoted\")"), vec!["quoted"]);
+    }
+
+    #[test]
+    fn test_contractions_and_hyphens() {
+        assert_eq!(
+            words("there's a well-known 'thing'"),
+            vec!["there's", "a", "well", "known", "thing"]
+        );
+        assert_eq!(words("don\u{2019}t"), vec!["don\u{2019}t"]);
+    }
+
+    #[test]
+    fn test_skips_non_words() {
+        assert_eq!(
+            words("call 555-1234 or mail bob@example.com, see https://x.org/a-b 3rd"),
+            vec!["call", "or", "mail", "see"]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "héllo, wörld";
+        let tokens: Vec<_> = Tokenizer::new(text).collect();
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(&text[tokens[1].byte_range.clone()], "wörld");
+        assert_eq!(tokens[1].byte_range, 8..14);
+        assert_eq!(tokens[1].char_range, 7..12);
+    }
+
+    #[test]
+    fn test_identifier_splitting() {
+        let identifiers = |text| -> Vec<&str> {
+            Tokenizer::new(text)
+                .with_identifier_splitting(true)
+                .map(|t| t.text)
+                .collect()
+        };
+
+        assert_eq!(identifiers("recieveMessage"), vec!["recieve", "Message"]);
+        assert_eq!(
+            identifiers("HTTPServerError"),
+            vec!["HTTP", "Server", "Error"]
+        );
+        assert_eq!(
+            identifiers("max_retrys_count kebab-case"),
+            vec!["max", "retrys", "count", "kebab", "case"]
+        );
+        assert_eq!(identifiers("utf8Encoder v2"), vec!["utf", "Encoder", "v"]);
+        assert_eq!(words("recieveMessage utf8"), vec!["recieveMessage"]);
+
+        let tokens: Vec<_> = Tokenizer::new("fooBär")
+            .with_identifier_splitting(true)
+            .collect();
+        assert_eq!(tokens[1].byte_range, 3..7);
+        assert_eq!(tokens[1].char_range, 3..6);
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{automaton::Transition, spell_checker::Corrector, trie::Trie};
+
+#[derive(Debug, Clone)]
+enum Output {
+    Copy,
+    Emit(String),
+}
+
+#[derive(Debug, Clone)]
+struct RuleArc {
+    input: Transition,
+    output: Output,
+    to: usize,
+    weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Transducer {
+    states: Vec<Vec<RuleArc>>,
+    final_states: BTreeSet<usize>,
+    max_cost: f64,
+}
+
+impl Default for Transducer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transducer {
+    pub fn new() -> Self {
+        Self {
+            states: vec![vec![RuleArc {
+                input: Transition::Star,
+                output: Output::Copy,
+                to: 0,
+                weight: 0.0,
+            }]],
+            final_states: BTreeSet::from([0]),
+            max_cost: 2.0,
+        }
+    }
+
+    pub fn english() -> Self {
+        Self::new()
+            .with_swap("f", "ph", 1.0)
+            .with_swap("ie", "ei", 1.0)
+            .with_swap("our", "or", 0.5)
+            .with_swap("re", "er", 0.5)
+            .with_swap("ise", "ize", 0.5)
+            .with_swap("yse", "yze", 0.5)
+            .with_swap("ce", "se", 0.5)
+            .with_swap("ogue", "og", 0.5)
+            .with_swap("ll", "l", 0.5)
+            .with_swap("ae", "e", 0.5)
+            .with_swap("oe", "e", 0.5)
+    }
+
+    pub fn with_max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost = max_cost;
+        self
+    }
+
+    pub fn with_rule(mut self, from: &str, to: &str, weight: f64) -> Self {
+        let chars: Vec<char> = from.chars().collect();
+        let mut state = 0;
+        for (i, c) in chars.iter().enumerate() {
+            let last = i + 1 == chars.len();
+            let to_state = match last {
+                true => 0,
+                false => {
+                    self.states.push(vec![]);
+                    self.states.len() - 1
+                }
+            };
+            self.states[state].push(RuleArc {
+                input: Transition::Is(*c),
+                output: Output::Emit(if last { to.to_string() } else { String::new() }),
+                to: to_state,
+                weight: if i == 0 { weight } else { 0.0 },
+            });
+            state = to_state;
+        }
+        self
+    }
+
+    pub fn with_swap(self, a: &str, b: &str, weight: f64) -> Self {
+        self.with_rule(a, b, weight).with_rule(b, a, weight)
+    }
+
+    pub fn transduce(&self, word: &str, trie: &Trie) -> Vec<(String, f64)> {
+        let automaton = trie.get_automaton();
+        let Some(trie_start) = automaton.start_state() else {
+            return vec![];
+        };
+        let best = self.search(
+            word,
+            trie_start,
+            |s, c| automaton.step(s, c),
+            |s| automaton.is_final(s),
+        );
+
+        let mut candidates: Vec<(String, f64)> = best.into_iter().collect();
+        candidates.sort_by(|(a, a_cost), (b, b_cost)| a_cost.total_cmp(b_cost).then(a.cmp(b)));
+        candidates
+    }
+
+    pub fn cost(&self, word: &str, candidate: &str) -> Option<f64> {
+        let target: Vec<char> = candidate.chars().collect();
+        self.search(
+            word,
+            0,
+            |i, c| (target.get(i) == Some(&c)).then_some(i + 1),
+            |i| i == target.len(),
+        )
+        .remove(candidate)
+    }
+
+    fn search<S: Copy>(
+        &self,
+        word: &str,
+        start: S,
+        step: impl Fn(S, char) -> Option<S>,
+        is_final: impl Fn(S) -> bool,
+    ) -> BTreeMap<String, f64> {
+        let chars: Vec<char> = word.chars().collect();
+
+        let mut best: BTreeMap<String, f64> = BTreeMap::new();
+        let mut stack = vec![(0, 0, start, String::new(), 0.0)];
+        while let Some((pos, state, trie_state, output, cost)) = stack.pop() {
+            let Some(&c) = chars.get(pos) else {
+                if self.final_states.contains(&state) && is_final(trie_state) {
+                    let entry = best.entry(output).or_insert(f64::INFINITY);
+                    *entry = entry.min(cost);
+                }
+                continue;
+            };
+
+            for arc in &self.states[state] {
+                let cost = cost + arc.weight;
+                if !arc.input.allows(c) || cost > self.max_cost {
+                    continue;
+                }
+                let emitted = match &arc.output {
+                    Output::Copy => c.to_string(),
+                    Output::Emit(text) => text.clone(),
+                };
+                let trie_state = emitted.chars().try_fold(trie_state, &step);
+                if let Some(trie_state) = trie_state {
+                    stack.push((pos + 1, arc.to, trie_state, output.clone() + &emitted, cost));
+                }
+            }
+        }
+
+        best
+    }
+
+    pub fn correct(&self, word: &str, trie: &Trie) -> Vec<(String, f64)> {
+        self.transduce(word, trie)
+            .into_iter()
+            .filter(|(candidate, _)| candidate != word)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleCorrector<C> {
+    rules: Transducer,
+    corrector: C,
+}
+
+impl<C> RuleCorrector<C>
+where
+    C: Corrector,
+{
+    pub fn new(rules: Transducer, corrector: C) -> Self {
+        Self { rules, corrector }
+    }
+}
+
+impl<C> Corrector for RuleCorrector<C>
+where
+    C: Corrector,
+{
+    fn candidates(&self, word: &str, tries: &[&Trie]) -> Vec<String> {
+        let mut candidates: Vec<String> = tries
+            .iter()
+            .flat_map(|trie| self.rules.correct(word, trie))
+            .map(|(candidate, _)| candidate)
+            .collect();
+        candidates.extend(self.corrector.candidates(word, tries));
+        candidates
+    }
+
+    fn score(&self, word: &str, candidate: &str) -> f64 {
+        let score = self.corrector.score(word, candidate);
+        match self.rules.cost(word, candidate) {
+            Some(cost) => score.min(cost),
+            None => score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        automaton::Deterministic,
+        levenshtein_automaton::LevenshteinAutomaton,
+        spell_checker::{EscalationPolicy, SpellChecker},
+        transducer::{RuleCorrector, Transducer},
+        trie::Trie,
+    };
+
+    #[test]
+    fn test_rewrite_rules() {
+        let trie = Trie::new(&mut [
+            "color",
+            "colour",
+            "photograph",
+            "receive",
+            "center",
+            "theatre",
+            "fork",
+        ]);
+        let rules = Transducer::english();
+
+        assert_eq!(
+            rules.transduce("fotograf", &trie),
+            vec![("photograph".into(), 2.0)]
+        );
+        assert_eq!(
+            rules.correct("recieve", &trie),
+            vec![("receive".into(), 1.0)]
+        );
+        assert_eq!(rules.correct("centre", &trie), vec![("center".into(), 0.5)]);
+        assert_eq!(
+            rules.correct("theater", &trie),
+            vec![("theatre".into(), 0.5)]
+        );
+        assert_eq!(rules.correct("color", &trie), vec![("colour".into(), 0.5)]);
+        assert!(
+            rules
+                .correct("phork", &trie)
+                .contains(&("fork".into(), 1.0))
+        );
+        assert_eq!(rules.cost("fotograf", "photograph"), Some(2.0));
+        assert_eq!(rules.cost("fotograf", "fotografs"), None);
+        assert!(
+            rules
+                .with_max_cost(1.0)
+                .correct("fotograf", &trie)
+                .is_empty()
+        );
+
+        let aut: LevenshteinAutomaton<Deterministic> =
+            LevenshteinAutomaton::new("fotograf", 2).into();
+        assert!(trie.filter(&aut).is_empty());
+
+        let words = || Trie::new(&mut ["kiosk", "kiosks"]);
+        let plain = SpellChecker::from_corrector(words(), EscalationPolicy::default());
+        assert_eq!(plain.check_word("kiosx"), Err(vec!["kiosk".into()]));
+        let custom = Transducer::new().with_rule("x", "ks", 0.5);
+        let spell_checker = SpellChecker::from_corrector(
+            words(),
+            RuleCorrector::new(custom, EscalationPolicy::default()),
+        );
+        assert_eq!(
+            spell_checker.check_word("kiosx"),
+            Err(vec!["kiosks".into(), "kiosk".into()])
+        );
+    }
+}
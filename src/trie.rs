@@ -1,17 +1,57 @@
 use std::{
     fs::File,
-    io::{self, BufRead, BufReader},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
     path::Path,
 };
 
 use itertools::Itertools;
 
-use crate::automaton::{Automaton, Deterministic, StateId, Transition};
+use crate::{
+    automaton::{Automaton, Deterministic, StateId, Transition},
+    hunspell::Hunspell,
+    matcher::Matcher,
+};
+
+const INDEX_MAGIC: &[u8; 8] = b"LEVTRIE1";
 
 #[derive(Debug, Clone)]
 pub struct Trie(Automaton<Deterministic>);
 
 impl Trie {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        if path.extension().is_some_and(|ext| ext == "dic") {
+            return Ok(Hunspell::load(path, &path.with_extension("aff"))?.to_trie());
+        }
+
+        let mut magic = [0; INDEX_MAGIC.len()];
+        let is_index = File::open(path)?.read_exact(&mut magic).is_ok() && magic == *INDEX_MAGIC;
+        if is_index {
+            Self::load_index(path)
+        } else {
+            Self::load_from_file(path)
+        }
+    }
+
+    pub fn load_index(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; INDEX_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != *INDEX_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a lev-automaton index",
+            ));
+        }
+        Ok(Self(Automaton::<Deterministic>::read_from(&mut reader)?))
+    }
+
+    pub fn save_index(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(INDEX_MAGIC)?;
+        self.0.write_to(&mut writer)?;
+        writer.flush()
+    }
+
     pub fn load_from_file(path: &Path) -> io::Result<Self> {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
@@ -56,12 +96,86 @@ impl Trie {
             .collect::<Vec<_>>();
     }
 
+    pub fn insert(&mut self, word: &str) -> bool {
+        if word.is_empty() {
+            return false;
+        }
+        let mut state = match self.0.start_state() {
+            Some(state) => state,
+            None => self.0.add_state(),
+        };
+        for c in word.chars() {
+            state = match self.0.step(state, c) {
+                Some(next) => next,
+                None => {
+                    let next = self.0.add_state();
+                    let transition_added = self.0.add_transition(state, next, Transition::Is(c));
+                    debug_assert!(transition_added);
+                    next
+                }
+            };
+        }
+        if self.0.is_final(state) {
+            return false;
+        }
+        self.0.make_state_final(state);
+        true
+    }
+
+    pub fn get_automaton(&self) -> &Automaton<Deterministic> {
+        &self.0
+    }
+
     pub fn contains(&self, word: &str) -> bool {
         self.0.recognizes(word)
     }
 
-    pub fn filter(&self, automata: &Automaton<Deterministic>) -> Vec<String> {
-        automata.intersect(&self.0)
+    pub fn prefixes(&self, word: &str) -> Vec<usize> {
+        let mut prefixes = vec![];
+        let Some(mut state) = self.0.start_state() else {
+            return prefixes;
+        };
+
+        for (idx, c) in word.char_indices() {
+            match self.0.step(state, c) {
+                Some(next) => state = next,
+                None => break,
+            }
+            if self.0.is_final(state) {
+                prefixes.push(idx + c.len_utf8());
+            }
+        }
+
+        prefixes
+    }
+
+    pub fn filter<M: Matcher + ?Sized>(&self, matcher: &M) -> Vec<String> {
+        let mut words = vec![];
+        let Some(start) = self.0.start_state() else {
+            return words;
+        };
+
+        let mut stack = vec![(String::new(), start, matcher.start())];
+        while let Some((word, trie_state, state)) = stack.pop() {
+            for (transition, next) in self.0.transitions(trie_state) {
+                let Transition::Is(c) = transition else {
+                    continue;
+                };
+                let next_state = matcher.accept(&state, *c);
+                if !matcher.can_match(&next_state) {
+                    continue;
+                }
+
+                let mut new_word = word.clone();
+                new_word.push(*c);
+                if self.0.is_final(*next) && matcher.is_match(&next_state) {
+                    words.push(new_word.clone());
+                }
+                stack.push((new_word, *next, next_state));
+            }
+        }
+
+        words
     }
 }
 
@@ -74,8 +188,12 @@ fn suffix_of(word: &str) -> &str {
 
 #[cfg(test)]
 mod test {
+    use std::{env, fs, io::ErrorKind, process};
 
-    use crate::trie::Trie;
+    use crate::{
+        automaton::{Automaton, NonDeterministic, Transition},
+        trie::{INDEX_MAGIC, Trie},
+    };
 
     #[test]
     fn test_trie() {
@@ -89,5 +207,75 @@ mod test {
         assert!(!trie.contains("asdf"));
         assert!(!trie.contains("bi"));
         assert!(!trie.contains(""));
+
+        let mut trie = trie;
+        assert!(trie.insert("bingo"));
+        assert!(trie.insert("bi"));
+        assert!(trie.insert("héllo"));
+        assert!(!trie.insert("bing"));
+        assert!(!trie.insert(""));
+        assert!(trie.contains("bingo"));
+        assert!(trie.contains("bi"));
+        assert!(trie.contains("héllo"));
+        assert!(!trie.contains("b"));
+        assert!(!trie.contains("bingos"));
+    }
+
+    #[test]
+    fn test_index_roundtrip() {
+        let mut words: Vec<&str> = vec!["asd", "bin", "bing", "bong", "héllo"];
+        let trie = Trie::new(words.as_mut_slice());
+
+        let path = env::temp_dir().join(format!("lev-automaton-index-{}.bin", process::id()));
+        trie.save_index(&path).unwrap();
+        let loaded = Trie::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        for word in words {
+            assert!(loaded.contains(word));
+        }
+        assert!(!loaded.contains("bi"));
+        assert_eq!(
+            loaded.get_automaton().to_dot(),
+            trie.get_automaton().to_dot()
+        );
+    }
+
+    #[test]
+    fn test_corrupt_index() {
+        let mut nfa = Automaton::<NonDeterministic>::default();
+        let start = nfa.add_state();
+        let end = nfa.add_state();
+        nfa.set_start_state(start);
+        nfa.make_state_final(end);
+        nfa.add_transition(start, end, Transition::Is('a'));
+
+        let path = env::temp_dir().join(format!("lev-automaton-corrupt-{}.bin", process::id()));
+        for transition in [Transition::Star, Transition::Epsilon] {
+            let mut nfa = nfa.clone();
+            nfa.add_transition(start, start, transition);
+            let mut bytes = INDEX_MAGIC.to_vec();
+            nfa.write_to(&mut bytes).unwrap();
+            fs::write(&path, bytes).unwrap();
+
+            let err = Trie::load(&path).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidData);
+        }
+
+        let mut bytes = INDEX_MAGIC.to_vec();
+        nfa.write_to(&mut bytes).unwrap();
+        fs::write(&path, bytes).unwrap();
+        assert!(Trie::load(&path).unwrap().contains("a"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let mut words: Vec<&str> = vec!["a", "at", "ate", "bin"];
+        let trie = Trie::new(words.as_mut_slice());
+
+        assert_eq!(trie.prefixes("atelier"), vec![1, 2, 3]);
+        assert_eq!(trie.prefixes("binge"), vec![3]);
+        assert!(trie.prefixes("bi").is_empty());
     }
 }